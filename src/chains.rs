@@ -1,67 +1,83 @@
+use crate::clients::{ClClient, ElClient};
 use crate::config::{ClConfig, ElConfig};
 use crate::layout::{bin_dir, data_dir, secret_dir};
 
-pub fn _hoodi_config() -> (ElConfig, ClConfig) {
-    let bin = bin_dir();
-    let data = data_dir();
-    let secrets = secret_dir();
-    let jwt = secrets.join("jwt.hex");
-
-    let el = ElConfig {
-        _name: "reth".to_string(),
-        bin: bin.join("reth"),
-        data_dir: data.join("reth-hoodi"),
-        chain: "hoodi".to_string(),
-        http_addr: "127.0.0.1".into(),
-        http_port: 8545,
-        authrpc_addr: "127.0.0.1".into(),
-        authrpc_port: 8551,
-        jwt_path: jwt.clone(),
-    };
+pub struct Chain {
+    pub name: &'static str,
+    pub chain_id: u64,
+    pub checkpoint_sync_url: Option<&'static str>,
+}
 
-    let cl = ClConfig {
-        _name: "lighthouse".to_string(),
-        bin: bin.join("lighthouse"),
-        data_dir: data.join("lighthouse-hoodi"),
-        chain: "hoodi".to_string(),
-        http_addr: "127.0.0.1".into(),
-        http_port: 5052,
-        execution_endpoint: el.authrpc_url(),
-        execution_jwt: jwt,
-        checkpoint_sync_url: Some("https://checkpoint-sync.hoodi.ethpandaops.io".to_string()),
-    };
+pub const CHAINS: &[Chain] = &[
+    Chain {
+        name: "mainnet",
+        chain_id: 1,
+        checkpoint_sync_url: Some("https://mainnet.checkpoint.sigp.io"),
+    },
+    Chain {
+        name: "hoodi",
+        chain_id: 560048,
+        checkpoint_sync_url: Some("https://checkpoint-sync.hoodi.ethpandaops.io"),
+    },
+    Chain {
+        name: "sepolia",
+        chain_id: 11155111,
+        checkpoint_sync_url: Some("https://checkpoint-sync.sepolia.ethpandaops.io"),
+    },
+    Chain {
+        name: "holesky",
+        chain_id: 17000,
+        checkpoint_sync_url: Some("https://checkpoint-sync.holesky.ethpandaops.io"),
+    },
+];
 
-    (el, cl)
+pub fn find_chain(name: &str) -> anyhow::Result<&'static Chain> {
+    CHAINS.iter().find(|c| c.name == name).ok_or_else(|| {
+        let known: Vec<&str> = CHAINS.iter().map(|c| c.name).collect();
+        anyhow::anyhow!(
+            "unknown chain '{}' (expected one of: {})",
+            name,
+            known.join(", ")
+        )
+    })
 }
 
-pub fn mainnet_config() -> (ElConfig, ClConfig) {
+pub fn chain_config(
+    chain: &Chain,
+    el_client: ElClient,
+    cl_client: ClClient,
+) -> (ElConfig, ClConfig) {
     let bin = bin_dir();
     let data = data_dir();
     let secrets = secret_dir();
     let jwt = secrets.join("jwt.hex");
 
     let el = ElConfig {
-        _name: "reth".to_string(),
-        bin: bin.join("reth"),
-        data_dir: data.join("reth-mainnet"),
-        chain: "mainnet".to_string(),
+        client: el_client,
+        bin: bin.join(el_client.binary()),
+        data_dir: data.join(format!("{}-{}", el_client, chain.name)),
+        chain: chain.name.to_string(),
+        chain_id: chain.chain_id,
         http_addr: "127.0.0.1".into(),
         http_port: 8545,
         authrpc_addr: "127.0.0.1".into(),
         authrpc_port: 8551,
         jwt_path: jwt.clone(),
+        embedded_cl: false,
+        extra_args: Vec::new(),
     };
 
     let cl = ClConfig {
-        _name: "lighthouse".to_string(),
-        bin: bin.join("lighthouse"),
-        data_dir: data.join("lighthouse-mainnet"),
-        chain: "mainnet".to_string(),
+        client: cl_client,
+        bin: bin.join(cl_client.binary()),
+        data_dir: data.join(format!("{}-{}", cl_client, chain.name)),
+        chain: chain.name.to_string(),
         http_addr: "127.0.0.1".into(),
         http_port: 5052,
         execution_endpoint: el.authrpc_url(),
         execution_jwt: jwt,
-        checkpoint_sync_url: Some("https://mainnet.checkpoint.sigp.io".to_string()),
+        checkpoint_sync_url: chain.checkpoint_sync_url.map(str::to_string),
+        extra_args: Vec::new(),
     };
 
     (el, cl)
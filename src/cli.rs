@@ -1,4 +1,10 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::clients::{ClClient, ElClient};
+use crate::config::{ClOverrides, ElOverrides};
 
 #[derive(Parser)]
 #[command(name = "eth", version, about = "ethereum made simple")]
@@ -12,6 +18,132 @@ pub enum Commands {
     Run {
         #[arg(short, long)]
         quiet: bool,
+        /// Full-screen view with log panes, live status and restart keys
+        #[arg(long, conflicts_with = "quiet")]
+        tui: bool,
+        /// Serve Prometheus metrics on this address, e.g. 127.0.0.1:9099
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<SocketAddr>,
+        /// How long each client gets to exit after SIGTERM before it is killed
+        #[arg(long, value_parser = humantime::parse_duration)]
+        grace_period: Option<Duration>,
+        // set by `eth start` for the supervisor it spawns
+        #[arg(long, hide = true)]
+        detached: bool,
+        #[command(flatten)]
+        node: NodeArgs,
+    },
+    /// Run the clients in the background
+    Start {
+        /// Serve Prometheus metrics on this address, e.g. 127.0.0.1:9099
+        #[arg(long, value_name = "ADDR")]
+        metrics_addr: Option<SocketAddr>,
+        #[command(flatten)]
+        node: NodeArgs,
+    },
+    /// Stop a running node
+    Stop {
+        /// Chain of the node to stop, needed when several are running
+        #[arg(long)]
+        chain: Option<String>,
+    },
+    /// Restart a running node's clients, keeping the mode it was started in
+    Restart {
+        /// Chain of the node to restart, needed when several are running
+        #[arg(long)]
+        chain: Option<String>,
+    },
+    Status {
+        #[command(flatten)]
+        node: NodeArgs,
+        #[arg(long, value_enum, default_value = "table")]
+        format: StatusFormat,
+        /// How long to wait for each RPC call before giving up on a client
+        #[arg(long, value_parser = humantime::parse_duration)]
+        timeout: Option<Duration>,
+        /// Keep refreshing, every 5s or the given interval, with sync rates and ETA
+        #[arg(
+            long,
+            value_name = "INTERVAL",
+            value_parser = humantime::parse_duration,
+            num_args = 0..=1,
+            default_missing_value = "5s"
+        )]
+        watch: Option<Duration>,
+    },
+    /// Install clients, optionally at a specific version: reth@v1.3.12 lighthouse@v7.0.1
+    Install {
+        #[arg(required = true, value_name = "CLIENT[@VERSION]")]
+        clients: Vec<String>,
+        /// Install a single client from a local release archive
+        #[arg(long, value_name = "ARCHIVE")]
+        from: Option<PathBuf>,
+    },
+    /// List installed client versions; the current one is marked with *
+    List,
+    /// Switch a client to another installed version: eth use reth v1.3.12
+    Use { client: String, version: String },
+    /// Remove a client version, or every version of it: reth@v1.3.12 or reth
+    Uninstall {
+        #[arg(required = true, value_name = "CLIENT[@VERSION]")]
+        clients: Vec<String>,
+    },
+    /// Upgrade installed clients to their latest release
+    Upgrade {
+        #[arg(default_value = "all", value_name = "CLIENT|all")]
+        client: String,
     },
-    Status,
+    /// Show client logs written in quiet and background mode
+    Logs {
+        #[arg(value_enum, default_value = "all")]
+        client: LogClient,
+        /// Chain of the node whose logs to show
+        #[arg(long)]
+        chain: Option<String>,
+        #[arg(short, long)]
+        follow: bool,
+        /// Show the last N lines [default: 50, or all of them with --since]
+        #[arg(short = 'n', long)]
+        lines: Option<usize>,
+        /// Only show lines matching this regular expression
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+        /// Only show lines newer than this, e.g. 10m or 2h
+        #[arg(long, value_parser = humantime::parse_duration)]
+        since: Option<Duration>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StatusFormat {
+    Table,
+    Json,
+    Yaml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LogClient {
+    El,
+    Cl,
+    All,
+}
+
+#[derive(Args, Default)]
+pub struct NodeArgs {
+    /// Network to run: mainnet, hoodi, sepolia or holesky
+    #[arg(long)]
+    pub chain: Option<String>,
+    /// Execution client to run
+    #[arg(long = "el", value_enum, value_name = "CLIENT")]
+    pub el_client: Option<ElClient>,
+    /// Consensus client to run
+    #[arg(long = "cl", value_enum, value_name = "CLIENT")]
+    pub cl_client: Option<ClClient>,
+    /// Use erigon's embedded Caplin consensus layer instead of a separate CL
+    #[arg(long)]
+    pub caplin: bool,
+    #[command(flatten)]
+    pub el: ElOverrides,
+    #[command(flatten)]
+    pub cl: ClOverrides,
 }
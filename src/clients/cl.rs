@@ -0,0 +1,169 @@
+use tokio::process::Command;
+
+use super::{ClClient, check_java};
+use crate::config::ClConfig;
+
+pub fn command(cfg: &ClConfig) -> Command {
+    match cfg.client {
+        ClClient::Lighthouse => lighthouse(cfg),
+        ClClient::Teku => teku(cfg),
+        ClClient::Prysm => prysm(cfg),
+        ClClient::Nimbus => nimbus(cfg),
+        ClClient::Lodestar => lodestar(cfg),
+        ClClient::Grandine => grandine(cfg),
+    }
+}
+
+// turns on debug logging, toggled with `l` in the TUI
+pub fn debug_logging(cmd: &mut Command, client: ClClient) {
+    match client {
+        ClClient::Lighthouse => cmd.args(["--debug-level", "debug"]),
+        ClClient::Teku => cmd.arg("--logging=DEBUG"),
+        ClClient::Prysm => cmd.args(["--verbosity", "debug"]),
+        ClClient::Nimbus => cmd.arg("--log-level=DEBUG"),
+        ClClient::Lodestar => cmd.args(["--logLevel", "debug"]),
+        // grandine has no flag for it and reads RUST_LOG instead
+        ClClient::Grandine => cmd.env("RUST_LOG", "debug"),
+    };
+}
+
+pub fn preflight(cfg: &ClConfig) -> anyhow::Result<()> {
+    match cfg.client {
+        ClClient::Teku => check_java("teku", 21),
+        _ => Ok(()),
+    }
+}
+
+fn lighthouse(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("bn")
+        .arg("--network")
+        .arg(&cfg.chain)
+        .arg("--listen-address")
+        .arg("0.0.0.0")
+        .arg("--datadir")
+        .arg(&cfg.data_dir)
+        .arg("--execution-endpoint")
+        .arg(&cfg.execution_endpoint)
+        .arg("--execution-jwt")
+        .arg(&cfg.execution_jwt)
+        .arg("--http")
+        .arg("--http-address")
+        .arg(&cfg.http_addr)
+        .arg("--http-port")
+        .arg(cfg.http_port.to_string());
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg("--checkpoint-sync-url").arg(url);
+    }
+
+    cmd
+}
+
+fn teku(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg(format!("--network={}", cfg.chain))
+        .arg(format!("--data-path={}", cfg.data_dir.display()))
+        .arg(format!("--ee-endpoint={}", cfg.execution_endpoint))
+        .arg(format!(
+            "--ee-jwt-secret-file={}",
+            cfg.execution_jwt.display()
+        ))
+        .arg("--rest-api-enabled=true")
+        .arg(format!("--rest-api-interface={}", cfg.http_addr))
+        .arg(format!("--rest-api-port={}", cfg.http_port));
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg(format!("--checkpoint-sync-url={}", url));
+    }
+
+    cmd
+}
+
+fn prysm(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg(format!("--{}", cfg.chain))
+        .arg("--accept-terms-of-use")
+        .arg("--datadir")
+        .arg(&cfg.data_dir)
+        .arg("--execution-endpoint")
+        .arg(&cfg.execution_endpoint)
+        .arg("--jwt-secret")
+        .arg(&cfg.execution_jwt)
+        .arg("--http-host")
+        .arg(&cfg.http_addr)
+        .arg("--http-port")
+        .arg(cfg.http_port.to_string());
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg("--checkpoint-sync-url")
+            .arg(url)
+            .arg("--genesis-beacon-api-url")
+            .arg(url);
+    }
+
+    cmd
+}
+
+fn nimbus(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg(format!("--network={}", cfg.chain))
+        .arg(format!("--data-dir={}", cfg.data_dir.display()))
+        .arg(format!("--el={}", cfg.execution_endpoint))
+        .arg(format!("--jwt-secret={}", cfg.execution_jwt.display()))
+        .arg("--rest")
+        .arg(format!("--rest-address={}", cfg.http_addr))
+        .arg(format!("--rest-port={}", cfg.http_port));
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg(format!("--external-beacon-api-url={}", url));
+    }
+
+    cmd
+}
+
+fn lodestar(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("beacon")
+        .arg("--network")
+        .arg(&cfg.chain)
+        .arg("--dataDir")
+        .arg(&cfg.data_dir)
+        .arg("--execution.urls")
+        .arg(&cfg.execution_endpoint)
+        .arg("--jwt-secret")
+        .arg(&cfg.execution_jwt)
+        .arg("--rest")
+        .arg("--rest.address")
+        .arg(&cfg.http_addr)
+        .arg("--rest.port")
+        .arg(cfg.http_port.to_string());
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg("--checkpointSyncUrl").arg(url);
+    }
+
+    cmd
+}
+
+fn grandine(cfg: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("--network")
+        .arg(&cfg.chain)
+        .arg("--data-dir")
+        .arg(&cfg.data_dir)
+        .arg("--eth1-rpc-urls")
+        .arg(&cfg.execution_endpoint)
+        .arg("--jwt-secret")
+        .arg(&cfg.execution_jwt)
+        .arg("--http-address")
+        .arg(&cfg.http_addr)
+        .arg("--http-port")
+        .arg(cfg.http_port.to_string());
+
+    if let Some(ref url) = cfg.checkpoint_sync_url {
+        cmd.arg("--checkpoint-sync-url").arg(url);
+    }
+
+    cmd
+}
@@ -0,0 +1,153 @@
+use tokio::process::Command;
+
+use super::{ElClient, check_java};
+use crate::config::{ClConfig, ElConfig};
+
+pub fn command(cfg: &ElConfig, cl: &ClConfig) -> Command {
+    match cfg.client {
+        ElClient::Reth => reth(cfg),
+        ElClient::Geth => geth(cfg),
+        ElClient::Nethermind => nethermind(cfg),
+        ElClient::Besu => besu(cfg),
+        ElClient::Erigon => erigon(cfg, cl),
+    }
+}
+
+// turns on debug logging, toggled with `l` in the TUI
+pub fn debug_logging(cmd: &mut Command, client: ElClient) {
+    match client {
+        ElClient::Reth => cmd.arg("-vvvv"),
+        ElClient::Geth => cmd.args(["--verbosity", "4"]),
+        ElClient::Nethermind => cmd.args(["--log", "DEBUG"]),
+        ElClient::Besu => cmd.arg("--logging=DEBUG"),
+        ElClient::Erigon => cmd.args(["--verbosity", "debug"]),
+    };
+}
+
+pub fn preflight(cfg: &ElConfig) -> anyhow::Result<()> {
+    if cfg.embedded_cl && cfg.client != ElClient::Erigon {
+        anyhow::bail!("--caplin is only supported with --el erigon");
+    }
+
+    match cfg.client {
+        ElClient::Besu => check_java("besu", 21),
+        _ => Ok(()),
+    }
+}
+
+fn reth(cfg: &ElConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("node")
+        .arg("--chain")
+        .arg(&cfg.chain)
+        .arg("--datadir")
+        .arg(&cfg.data_dir)
+        .arg("--authrpc.addr")
+        .arg(&cfg.authrpc_addr)
+        .arg("--authrpc.port")
+        .arg(cfg.authrpc_port.to_string())
+        .arg("--authrpc.jwtsecret")
+        .arg(&cfg.jwt_path)
+        .arg("--http")
+        .arg("--http.addr")
+        .arg(&cfg.http_addr)
+        .arg("--http.port")
+        .arg(cfg.http_port.to_string())
+        .arg("--http.api")
+        .arg("all");
+    cmd
+}
+
+fn geth(cfg: &ElConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg(format!("--{}", cfg.chain))
+        .arg("--datadir")
+        .arg(&cfg.data_dir)
+        .arg("--authrpc.addr")
+        .arg(&cfg.authrpc_addr)
+        .arg("--authrpc.port")
+        .arg(cfg.authrpc_port.to_string())
+        .arg("--authrpc.jwtsecret")
+        .arg(&cfg.jwt_path)
+        .arg("--http")
+        .arg("--http.addr")
+        .arg(&cfg.http_addr)
+        .arg("--http.port")
+        .arg(cfg.http_port.to_string())
+        .arg("--http.api")
+        .arg("eth,net,web3");
+    cmd
+}
+
+fn nethermind(cfg: &ElConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("--config")
+        .arg(&cfg.chain)
+        .arg("--data-dir")
+        .arg(&cfg.data_dir)
+        .arg("--JsonRpc.Enabled")
+        .arg("true")
+        .arg("--JsonRpc.Host")
+        .arg(&cfg.http_addr)
+        .arg("--JsonRpc.Port")
+        .arg(cfg.http_port.to_string())
+        .arg("--JsonRpc.EngineHost")
+        .arg(&cfg.authrpc_addr)
+        .arg("--JsonRpc.EnginePort")
+        .arg(cfg.authrpc_port.to_string())
+        .arg("--JsonRpc.JwtSecretFile")
+        .arg(&cfg.jwt_path);
+    cmd
+}
+
+fn besu(cfg: &ElConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg(format!("--network={}", cfg.chain))
+        .arg(format!("--data-path={}", cfg.data_dir.display()))
+        .arg("--rpc-http-enabled")
+        .arg(format!("--rpc-http-host={}", cfg.http_addr))
+        .arg(format!("--rpc-http-port={}", cfg.http_port))
+        .arg("--rpc-http-api=ETH,NET,WEB3")
+        .arg(format!("--engine-rpc-port={}", cfg.authrpc_port))
+        .arg(format!(
+            "--engine-host-allowlist=localhost,{}",
+            cfg.authrpc_addr
+        ))
+        .arg(format!("--engine-jwt-secret={}", cfg.jwt_path.display()));
+    cmd
+}
+
+fn erigon(cfg: &ElConfig, cl: &ClConfig) -> Command {
+    let mut cmd = Command::new(&cfg.bin);
+    cmd.arg("--chain")
+        .arg(&cfg.chain)
+        .arg("--datadir")
+        .arg(&cfg.data_dir)
+        .arg("--authrpc.addr")
+        .arg(&cfg.authrpc_addr)
+        .arg("--authrpc.port")
+        .arg(cfg.authrpc_port.to_string())
+        .arg("--authrpc.jwtsecret")
+        .arg(&cfg.jwt_path)
+        .arg("--http")
+        .arg("--http.addr")
+        .arg(&cfg.http_addr)
+        .arg("--http.port")
+        .arg(cfg.http_port.to_string())
+        .arg("--http.api")
+        .arg("eth,net,web3,erigon");
+
+    if cfg.embedded_cl {
+        // Caplin serves the beacon API where a separate CL would
+        cmd.arg("--beacon.api")
+            .arg("beacon,config,node")
+            .arg("--beacon.api.addr")
+            .arg(&cl.http_addr)
+            .arg("--beacon.api.port")
+            .arg(cl.http_port.to_string());
+    } else {
+        cmd.arg("--externalcl");
+    }
+
+    cmd
+}
@@ -0,0 +1,150 @@
+pub mod cl;
+pub mod el;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElClient {
+    Reth,
+    Geth,
+    Nethermind,
+    Besu,
+    Erigon,
+}
+
+impl ElClient {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ElClient::Reth => "reth",
+            ElClient::Geth => "geth",
+            ElClient::Nethermind => "nethermind",
+            ElClient::Besu => "besu",
+            ElClient::Erigon => "erigon",
+        }
+    }
+
+    pub fn binary(&self) -> &'static str {
+        self.name()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClClient {
+    Lighthouse,
+    Teku,
+    Prysm,
+    Nimbus,
+    Lodestar,
+    Grandine,
+}
+
+impl ClClient {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClClient::Lighthouse => "lighthouse",
+            ClClient::Teku => "teku",
+            ClClient::Prysm => "prysm",
+            ClClient::Nimbus => "nimbus",
+            ClClient::Lodestar => "lodestar",
+            ClClient::Grandine => "grandine",
+        }
+    }
+
+    pub fn binary(&self) -> &'static str {
+        match self {
+            ClClient::Prysm => "beacon-chain",
+            ClClient::Nimbus => "nimbus_beacon_node",
+            _ => self.name(),
+        }
+    }
+}
+
+impl fmt::Display for ClClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ClClient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_client(s)
+    }
+}
+
+impl fmt::Display for ElClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ElClient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_client(s)
+    }
+}
+
+fn parse_client<T: ValueEnum>(s: &str) -> Result<T, String> {
+    T::from_str(s, true).map_err(|_| {
+        let known: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        format!(
+            "unknown client '{}' (expected one of: {})",
+            s,
+            known.join(", ")
+        )
+    })
+}
+
+pub fn check_java(client: &str, min_version: u32) -> anyhow::Result<()> {
+    let java = match std::env::var_os("JAVA_HOME") {
+        Some(home) => std::path::PathBuf::from(home).join("bin/java"),
+        None => "java".into(),
+    };
+
+    let output = Command::new(&java).arg("-version").output().map_err(|_| {
+        anyhow::anyhow!(
+            "{} needs a Java {}+ runtime, but `{}` was not found; install a JDK or set JAVA_HOME",
+            client,
+            min_version,
+            java.display()
+        )
+    })?;
+
+    // `java -version` prints e.g. `openjdk version "21.0.2" 2024-01-16` to stderr
+    let text = String::from_utf8_lossy(&output.stderr);
+    let major = text
+        .split('"')
+        .nth(1)
+        .and_then(|v| v.split('.').next())
+        .and_then(|v| v.parse::<u32>().ok());
+
+    match major {
+        Some(major) if major >= min_version => Ok(()),
+        Some(major) => anyhow::bail!(
+            "{} needs Java {}+, but {} is Java {}",
+            client,
+            min_version,
+            java.display(),
+            major
+        ),
+        None => anyhow::bail!(
+            "{} needs Java {}+, but the version of {} could not be determined",
+            client,
+            min_version,
+            java.display()
+        ),
+    }
+}
@@ -1,15 +1,23 @@
+use clap::Args;
+
+use crate::clients::{ClClient, ElClient};
+use serde::Deserialize;
 use std::path::PathBuf;
 
+#[derive(Clone)]
 pub struct ElConfig {
-    pub _name: String,
+    pub client: ElClient,
     pub bin: PathBuf,
     pub chain: String,
+    pub chain_id: u64,
     pub data_dir: PathBuf,
     pub http_addr: String,
     pub http_port: u16,
     pub authrpc_addr: String,
     pub authrpc_port: u16,
     pub jwt_path: PathBuf,
+    pub embedded_cl: bool,
+    pub extra_args: Vec<String>,
 }
 
 impl ElConfig {
@@ -22,8 +30,9 @@ impl ElConfig {
     }
 }
 
+#[derive(Clone)]
 pub struct ClConfig {
-    pub _name: String,
+    pub client: ClClient,
     pub bin: PathBuf,
     pub data_dir: PathBuf,
     pub chain: String,
@@ -32,6 +41,7 @@ pub struct ClConfig {
     pub execution_endpoint: String,
     pub execution_jwt: PathBuf,
     pub checkpoint_sync_url: Option<String>,
+    pub extra_args: Vec<String>,
 }
 
 impl ClConfig {
@@ -39,3 +49,139 @@ impl ClConfig {
         format!("http://{}:{}", self.http_addr, self.http_port)
     }
 }
+
+#[derive(Args, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ElOverrides {
+    #[arg(long = "el-http-addr", id = "el_http_addr", value_name = "ADDR")]
+    pub http_addr: Option<String>,
+    #[arg(long = "el-http-port", id = "el_http_port", value_name = "PORT")]
+    pub http_port: Option<u16>,
+    #[arg(long = "el-authrpc-addr", id = "el_authrpc_addr", value_name = "ADDR")]
+    pub authrpc_addr: Option<String>,
+    #[arg(long = "el-authrpc-port", id = "el_authrpc_port", value_name = "PORT")]
+    pub authrpc_port: Option<u16>,
+    #[arg(long = "el-data-dir", id = "el_data_dir", value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    #[arg(
+        long = "el-arg",
+        id = "el_arg",
+        value_name = "ARG",
+        allow_hyphen_values = true
+    )]
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+impl ElOverrides {
+    pub fn from_env(env: Env) -> anyhow::Result<Self> {
+        Ok(Self {
+            http_addr: env_var(env, "ETHUP_EL_HTTP_ADDR")?,
+            http_port: env_var(env, "ETHUP_EL_HTTP_PORT")?,
+            authrpc_addr: env_var(env, "ETHUP_EL_AUTHRPC_ADDR")?,
+            authrpc_port: env_var(env, "ETHUP_EL_AUTHRPC_PORT")?,
+            data_dir: env_var(env, "ETHUP_EL_DATA_DIR")?,
+            extra_args: env_args(env, "ETHUP_EL_EXTRA_ARGS"),
+        })
+    }
+
+    pub fn apply(&self, cfg: &mut ElConfig) {
+        if let Some(ref addr) = self.http_addr {
+            cfg.http_addr = addr.clone();
+        }
+        if let Some(port) = self.http_port {
+            cfg.http_port = port;
+        }
+        if let Some(ref addr) = self.authrpc_addr {
+            cfg.authrpc_addr = addr.clone();
+        }
+        if let Some(port) = self.authrpc_port {
+            cfg.authrpc_port = port;
+        }
+        if let Some(ref dir) = self.data_dir {
+            cfg.data_dir = dir.clone();
+        }
+        cfg.extra_args.extend(self.extra_args.iter().cloned());
+    }
+}
+
+#[derive(Args, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ClOverrides {
+    #[arg(long = "cl-http-addr", id = "cl_http_addr", value_name = "ADDR")]
+    pub http_addr: Option<String>,
+    #[arg(long = "cl-http-port", id = "cl_http_port", value_name = "PORT")]
+    pub http_port: Option<u16>,
+    #[arg(long = "cl-data-dir", id = "cl_data_dir", value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Checkpoint sync URL; pass an empty string to sync from genesis
+    #[arg(long)]
+    pub checkpoint_sync_url: Option<String>,
+    #[arg(
+        long = "cl-arg",
+        id = "cl_arg",
+        value_name = "ARG",
+        allow_hyphen_values = true
+    )]
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+impl ClOverrides {
+    pub fn from_env(env: Env) -> anyhow::Result<Self> {
+        Ok(Self {
+            http_addr: env_var(env, "ETHUP_CL_HTTP_ADDR")?,
+            http_port: env_var(env, "ETHUP_CL_HTTP_PORT")?,
+            data_dir: env_var(env, "ETHUP_CL_DATA_DIR")?,
+            checkpoint_sync_url: env_var(env, "ETHUP_CL_CHECKPOINT_SYNC_URL")?,
+            extra_args: env_args(env, "ETHUP_CL_EXTRA_ARGS"),
+        })
+    }
+
+    pub fn apply(&self, cfg: &mut ClConfig) {
+        if let Some(ref addr) = self.http_addr {
+            cfg.http_addr = addr.clone();
+        }
+        if let Some(port) = self.http_port {
+            cfg.http_port = port;
+        }
+        if let Some(ref dir) = self.data_dir {
+            cfg.data_dir = dir.clone();
+        }
+        if let Some(ref url) = self.checkpoint_sync_url {
+            cfg.checkpoint_sync_url = if url.is_empty() {
+                None
+            } else {
+                Some(url.clone())
+            };
+        }
+        cfg.extra_args.extend(self.extra_args.iter().cloned());
+    }
+}
+
+// where ETHUP_* settings are looked up; the real environment outside tests
+pub type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+pub fn process_env(key: &str) -> Option<String> {
+    std::env::var(key).ok()
+}
+
+pub fn env_var<T>(env: Env, key: &str) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env(key) {
+        Some(val) => val
+            .parse()
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("invalid value for {}: {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+fn env_args(env: Env, key: &str) -> Vec<String> {
+    env(key)
+        .map(|v| v.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
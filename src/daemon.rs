@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::config::{ClConfig, ElConfig};
+use crate::layout::{log_dir, state_dir, state_file};
+
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    pub supervisor_pid: u32,
+    // start time of the supervisor, so a reused pid is not mistaken for it
+    #[serde(default)]
+    pub supervisor_started: Option<String>,
+    pub chain: String,
+    pub args: Vec<String>,
+    // started by `eth start`; a foreground supervisor restarts in place
+    #[serde(default)]
+    pub detached: bool,
+    pub el: ProcessState,
+    // None when the EL embeds its own consensus layer
+    pub cl: Option<ProcessState>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProcessState {
+    pub client: String,
+    // None while the client is down, e.g. waiting to be restarted
+    pub pid: Option<u32>,
+    pub http_port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authrpc_port: Option<u16>,
+    // beacon API port of a CL embedded in the EL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beacon_port: Option<u16>,
+}
+
+impl State {
+    pub fn new(
+        el: &ElConfig,
+        el_pid: Option<u32>,
+        cl: &ClConfig,
+        cl_pid: Option<u32>,
+        detached: bool,
+    ) -> Self {
+        Self {
+            supervisor_pid: std::process::id(),
+            supervisor_started: own_start_time(),
+            chain: el.chain.clone(),
+            args: std::env::args()
+                .skip(2)
+                .filter(|a| !["-q", "--quiet", "--tui", "--detached"].contains(&a.as_str()))
+                .collect(),
+            detached,
+            el: ProcessState {
+                client: el.client.to_string(),
+                pid: el_pid,
+                http_port: el.http_port,
+                authrpc_port: Some(el.authrpc_port),
+                beacon_port: el.embedded_cl.then_some(cl.http_port),
+            },
+            cl: (!el.embedded_cl).then(|| ProcessState {
+                client: cl.client.to_string(),
+                pid: cl_pid,
+                http_port: cl.http_port,
+                authrpc_port: None,
+                beacon_port: None,
+            }),
+        }
+    }
+
+    // the recorded supervisor is still the process behind its pid; after a
+    // SIGKILL or the OOM killer the state file stays and the pid may be reused
+    fn supervisor_alive(&self) -> bool {
+        if !is_alive(self.supervisor_pid) {
+            return false;
+        }
+        match self.supervisor_started {
+            Some(ref started) => start_time(self.supervisor_pid).as_ref() == Some(started),
+            None => true,
+        }
+    }
+
+    pub fn uses(&self, client: &str) -> bool {
+        self.el.client == client || self.cl.as_ref().is_some_and(|cl| cl.client == client)
+    }
+
+    fn client_pids(&self) -> Vec<u32> {
+        let mut pids: Vec<u32> = self.el.pid.into_iter().collect();
+        pids.extend(self.cl.as_ref().and_then(|cl| cl.pid));
+        pids
+    }
+}
+
+fn describe_pid(pid: Option<u32>) -> String {
+    match pid {
+        Some(pid) => format!("pid {}", pid),
+        None => "down".to_string(),
+    }
+}
+
+fn print_clients(state: &State) {
+    println!("  EL {} {}", state.el.client, describe_pid(state.el.pid));
+    match state.cl {
+        Some(ref cl) => println!("  CL {} {}", cl.client, describe_pid(cl.pid)),
+        None => println!("  CL embedded in {}", state.el.client),
+    }
+}
+
+pub fn read_state(chain: &str) -> anyhow::Result<Option<State>> {
+    let path = state_file(chain);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&raw)?))
+}
+
+pub fn write_state(state: &State) -> anyhow::Result<()> {
+    let path = state_file(&state.chain);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(state)?)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
+
+pub fn clear_state(chain: &str) {
+    let _ = std::fs::remove_file(state_file(chain));
+}
+
+pub fn running(chain: &str) -> anyhow::Result<Option<State>> {
+    match read_state(chain)? {
+        Some(state) if state.supervisor_alive() => Ok(Some(state)),
+        Some(_) => {
+            clear_state(chain);
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+// the nodes running on any chain, ordered by chain
+pub fn running_all() -> anyhow::Result<Vec<State>> {
+    let entries = match std::fs::read_dir(state_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut states = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        if let Some(chain) = path.file_stem().and_then(|s| s.to_str())
+            && let Some(state) = running(chain)?
+        {
+            states.push(state);
+        }
+    }
+    states.sort_by(|a, b| a.chain.cmp(&b.chain));
+
+    Ok(states)
+}
+
+pub fn is_alive(pid: u32) -> bool {
+    let ret = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+// `ps` works on both linux and macos, unlike /proc
+fn parent_pid(pid: u32) -> Option<u32> {
+    let out = Command::new("ps")
+        .args(["-o", "ppid=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout).trim().parse().ok()
+}
+
+fn start_time(pid: u32) -> Option<String> {
+    let out = Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let started = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (!started.is_empty()).then_some(started)
+}
+
+fn own_start_time() -> Option<String> {
+    static STARTED: OnceLock<Option<String>> = OnceLock::new();
+    STARTED
+        .get_or_init(|| start_time(std::process::id()))
+        .clone()
+}
+
+pub fn send_signal(pid: u32, signal: libc::c_int) {
+    unsafe {
+        libc::kill(pid as libc::pid_t, signal);
+    }
+}
+
+pub async fn start(chain: &str, args: &[String]) -> anyhow::Result<()> {
+    if let Some(state) = running(chain)? {
+        anyhow::bail!(
+            "ethup is already running on {} (pid {}); use `eth restart --chain {}` or `eth stop --chain {}`",
+            chain,
+            state.supervisor_pid,
+            chain,
+            chain
+        );
+    }
+
+    std::fs::create_dir_all(log_dir(chain))?;
+    let log_path = log_dir(chain).join("ethup.log");
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(["run", "--quiet", "--detached"])
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+
+    // detach from the controlling terminal so the supervisor survives it
+    unsafe {
+        cmd.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+
+    let mut child = cmd.spawn()?;
+    let deadline = Instant::now() + Duration::from_secs(15);
+
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!(
+                "supervisor exited with {} during startup, see {}",
+                status,
+                log_path.display()
+            );
+        }
+
+        if let Some(state) = read_state(chain)?
+            && state.supervisor_pid == child.id()
+        {
+            println!("Started ethup on {} (pid {})", state.chain, child.id());
+            print_clients(&state);
+            println!("Supervisor log at {}", log_path.display());
+            return Ok(());
+        }
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    anyhow::bail!(
+        "supervisor did not report its clients in time, see {}",
+        log_path.display()
+    )
+}
+
+pub async fn stop(chain: &str, timeout: Duration) -> anyhow::Result<Option<State>> {
+    let Some(state) = running(chain)? else {
+        return Ok(None);
+    };
+
+    println!(
+        "Stopping ethup on {} (pid {})...",
+        chain, state.supervisor_pid
+    );
+    send_signal(state.supervisor_pid, libc::SIGTERM);
+
+    // the supervisor stops its clients before it exits, so waiting on it is
+    // enough; recorded client pids are only trusted while they are still its
+    // children, since a dead client's pid may have been reused
+    let deadline = Instant::now() + timeout;
+    while state.supervisor_alive() {
+        if Instant::now() >= deadline {
+            for pid in state.client_pids() {
+                if parent_pid(pid) == Some(state.supervisor_pid) {
+                    eprintln!("pid {} did not exit in time, killing it", pid);
+                    send_signal(pid, libc::SIGKILL);
+                }
+            }
+            eprintln!(
+                "supervisor pid {} did not exit in time, killing it",
+                state.supervisor_pid
+            );
+            send_signal(state.supervisor_pid, libc::SIGKILL);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    clear_state(chain);
+    println!("Stopped");
+
+    Ok(Some(state))
+}
+
+// restarts the node in the mode it was started in: a background node is
+// started again with its arguments, a foreground one restarts its clients
+// in place so its terminal or TUI stays attached
+pub async fn restart(state: &State, timeout: Duration) -> anyhow::Result<()> {
+    if !state.detached {
+        return restart_in_place(state, timeout).await;
+    }
+
+    stop(&state.chain, timeout).await?;
+    start(&state.chain, &state.args).await
+}
+
+pub async fn restart_in_place(state: &State, timeout: Duration) -> anyhow::Result<()> {
+    println!(
+        "Restarting the clients of ethup on {} (pid {})...",
+        state.chain, state.supervisor_pid
+    );
+    send_signal(state.supervisor_pid, libc::SIGUSR1);
+
+    // the supervisor records the new pids once both clients are back
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        match running(&state.chain)? {
+            Some(now) if now.el.pid.is_some() && now.el.pid != state.el.pid => {
+                println!("Restarted");
+                print_clients(&now);
+                return Ok(());
+            }
+            Some(_) => {}
+            None => anyhow::bail!(
+                "ethup on {} exited while restarting its clients",
+                state.chain
+            ),
+        }
+    }
+
+    anyhow::bail!(
+        "ethup on {} did not report its restarted clients in time",
+        state.chain
+    )
+}
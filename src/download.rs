@@ -0,0 +1,146 @@
+use futures_util::StreamExt;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use reqwest::{StatusCode, Url, header};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+const ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// downloads `url` to `dest`, resuming a partial `dest` left by an earlier
+// attempt and retrying transient failures with exponential backoff
+pub async fn download(url: &Url, dest: &Path) -> anyhow::Result<()> {
+    if url.scheme() == "file" {
+        tokio::fs::copy(file_path(url)?, dest).await?;
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("ethup")
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()?;
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let bar = progress_bar(&name);
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match download_once(&client, url, dest, &bar).await {
+            Ok(()) => {
+                bar.finish_and_clear();
+                return Ok(());
+            }
+            Err(e) if attempt < ATTEMPTS && is_transient(&e) => {
+                bar.suspend(|| {
+                    eprintln!(
+                        "download of {} interrupted ({}), retrying in {}s",
+                        name,
+                        e,
+                        backoff.as_secs()
+                    )
+                });
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => {
+                bar.abandon();
+                return Err(e);
+            }
+        }
+    }
+}
+
+async fn download_once(
+    client: &reqwest::Client,
+    url: &Url,
+    dest: &Path,
+    bar: &ProgressBar,
+) -> anyhow::Result<()> {
+    let offset = match tokio::fs::metadata(dest).await {
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    };
+
+    let mut request = client.get(url.clone());
+    if offset > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request.send().await?;
+
+    // the partial file already holds everything the server has; the checksum
+    // check afterwards catches a stale file
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+    let response = response.error_for_status()?;
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut file = if resumed {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(dest)
+            .await?
+    } else {
+        tokio::fs::File::create(dest).await?
+    };
+    let start = if resumed { offset } else { 0 };
+
+    match response.content_length() {
+        Some(len) => bar.set_length(start + len),
+        None => bar.unset_length(),
+    }
+    bar.set_position(start);
+    bar.reset_eta();
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk).await?;
+        bar.inc(chunk.len() as u64);
+    }
+
+    file.flush().await?;
+    Ok(())
+}
+
+fn is_transient(e: &anyhow::Error) -> bool {
+    let Some(e) = e.downcast_ref::<reqwest::Error>() else {
+        return false;
+    };
+
+    match e.status() {
+        Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        None => e.is_timeout() || e.is_connect() || e.is_body() || e.is_decode() || e.is_request(),
+    }
+}
+
+fn progress_bar(name: &str) -> ProgressBar {
+    if !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::stdout());
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{msg} [{bar:30}] {bytes}/{total_bytes} {bytes_per_sec} eta {eta}",
+        )
+        .unwrap()
+        .progress_chars("=> "),
+    );
+    bar.set_message(name.to_string());
+    bar
+}
+
+pub fn file_path(url: &Url) -> anyhow::Result<PathBuf> {
+    url.to_file_path()
+        .map_err(|_| anyhow::anyhow!("invalid file url {}", url))
+}
@@ -1,147 +1,985 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use flate2::read::GzDecoder;
-use futures_util::StreamExt;
 use rand::TryRngCore;
 use rand::rand_core::OsRng;
-use reqwest::{Url, get};
+use reqwest::Url;
 use serde::Deserialize;
-use std::fs::{File, metadata, set_permissions};
+use std::fmt;
+use std::fs::{File, set_permissions};
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tar::Archive;
-use tokio::io::AsyncWriteExt;
 
+use crate::clients::check_java;
+use crate::download::{download, file_path};
 use crate::layout::*;
+use crate::manifest::Manifest;
+use crate::settings::{Settings, VerifySettings};
+use crate::verify::verify_download;
+
+const GETHSTORE_URL: &str = "https://gethstore.blob.core.windows.net/builds";
+const SMOKE_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+const STALE_TMP_AGE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
 
 #[derive(Deserialize)]
-struct Release {
-    tag_name: String,
-    assets: Vec<Asset>,
+pub struct Release {
+    pub tag_name: String,
+    pub assets: Vec<Asset>,
 }
 
 #[derive(Deserialize)]
-struct Asset {
-    name: String,
-    browser_download_url: Url,
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: Url,
+    pub digest: Option<String>,
 }
 
-pub async fn download_reth() -> anyhow::Result<()> {
-    let client = reqwest::Client::new();
+#[derive(Debug)]
+pub enum InstallError {
+    UnknownClient(String),
+    UnsupportedPlatform {
+        os: String,
+        arch: String,
+    },
+    ReleaseNotFound {
+        repo: String,
+        version: String,
+        status: u16,
+    },
+    RateLimited {
+        repo: String,
+        status: u16,
+    },
+    NoMatchingAsset {
+        client: String,
+        version: String,
+        expected: String,
+    },
+    MissingBinary {
+        client: String,
+        binary: String,
+    },
+}
 
-    let release: Release = client
-        .get("https://api.github.com/repos/paradigmxyz/reth/releases/latest")
-        .header("User-Agent", "ethup")
-        .send()
-        .await?
-        .json()
-        .await?;
+impl fmt::Display for InstallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallError::UnknownClient(name) => write!(
+                f,
+                "unknown client '{}' (expected one of: {})",
+                name,
+                client_names().join(", ")
+            ),
+            InstallError::UnsupportedPlatform { os, arch } => {
+                write!(f, "unsupported platform {}-{}", os, arch)
+            }
+            InstallError::ReleaseNotFound {
+                repo,
+                version,
+                status,
+            } => write!(
+                f,
+                "no release {} found for {} (HTTP {})",
+                version, repo, status
+            ),
+            InstallError::RateLimited { repo, status } => write!(
+                f,
+                "GitHub rate limited the release lookup for {} (HTTP {}); try again later or use a mirror",
+                repo, status
+            ),
+            InstallError::NoMatchingAsset {
+                client,
+                version,
+                expected,
+            } => write!(
+                f,
+                "{} {} has no release asset matching {}",
+                client, version, expected
+            ),
+            InstallError::MissingBinary { client, binary } => {
+                write!(f, "{} archive does not contain {}", client, binary)
+            }
+        }
+    }
+}
 
-    let target_os = match std::env::consts::OS {
-        "macos" => "apple-darwin",
-        "linux" => "unknown-linux-gnu",
-        "windows" => "pc-windows-gnu",
-        _ => panic!("unsupported OS"),
-    };
+impl std::error::Error for InstallError {}
 
-    let release_name = format!(
-        "reth-{}-{}-{}.tar.gz",
-        release.tag_name,
-        std::env::consts::ARCH,
-        target_os
-    );
+pub enum ArchiveFormat {
+    TarGz,
+    Zip,
+    Raw,
+}
 
-    let download_url = release
-        .assets
+// every version is unpacked to versions_dir()/<client>/<tag>, with a
+// `current` symlink next to it; bin_dir() only holds links through `current`
+pub enum InstallLayout {
+    // standalone executables
+    Binaries(&'static [&'static str]),
+    // a distribution that needs its whole directory tree, started through
+    // `entrypoint` inside it
+    Tree {
+        binary: &'static str,
+        entrypoint: &'static str,
+    },
+}
+
+pub enum AssetSource {
+    GitHub,
+    // geth publishes its archives to an azure blob store, not to GitHub
+    GethStore,
+}
+
+pub struct ClientInstaller {
+    pub name: &'static str,
+    pub repo: &'static str,
+    pub source: AssetSource,
+    pub tag_prefix: &'static str,
+    // supports {version}, {version_num}, {os}, {arch}, {goarch}, {target},
+    // {dotnet_os}, {dotnet_arch}, {nimbus_os} and `*` wildcards
+    pub asset_template: &'static str,
+    pub layout: InstallLayout,
+    pub archive: ArchiveFormat,
+    // minimum Java major version the release runs on
+    pub java: Option<u32>,
+}
+
+pub const INSTALLERS: &[ClientInstaller] = &[
+    ClientInstaller {
+        name: "reth",
+        repo: "paradigmxyz/reth",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "reth-{version}-{arch}-{target}.tar.gz",
+        layout: InstallLayout::Binaries(&["reth"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "lighthouse",
+        repo: "sigp/lighthouse",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "lighthouse-{version}-{arch}-{target}.tar.gz",
+        layout: InstallLayout::Binaries(&["lighthouse"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "teku",
+        repo: "Consensys/teku",
+        source: AssetSource::GitHub,
+        tag_prefix: "",
+        asset_template: "teku-{version_num}.tar.gz",
+        layout: InstallLayout::Tree {
+            binary: "teku",
+            entrypoint: "bin/teku",
+        },
+        archive: ArchiveFormat::TarGz,
+        java: Some(21),
+    },
+    ClientInstaller {
+        name: "prysm",
+        repo: "OffchainLabs/prysm",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "beacon-chain-{version}-{os}-{goarch}",
+        layout: InstallLayout::Binaries(&["beacon-chain"]),
+        archive: ArchiveFormat::Raw,
+        java: None,
+    },
+    ClientInstaller {
+        name: "nimbus",
+        repo: "status-im/nimbus-eth2",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "nimbus-eth2_{nimbus_os}_{goarch}_{version_num}_*.tar.gz",
+        layout: InstallLayout::Binaries(&["nimbus_beacon_node"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "lodestar",
+        repo: "ChainSafe/lodestar",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "lodestar-{version}-{os}-{goarch}.tar.gz",
+        layout: InstallLayout::Binaries(&["lodestar"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "grandine",
+        repo: "grandinetech/grandine",
+        source: AssetSource::GitHub,
+        tag_prefix: "",
+        asset_template: "grandine-{version_num}-{os}-{goarch}",
+        layout: InstallLayout::Binaries(&["grandine"]),
+        archive: ArchiveFormat::Raw,
+        java: None,
+    },
+    ClientInstaller {
+        name: "geth",
+        repo: "ethereum/go-ethereum",
+        source: AssetSource::GethStore,
+        tag_prefix: "v",
+        asset_template: "geth-{os}-{goarch}-{version_num}-*.tar.gz",
+        layout: InstallLayout::Binaries(&["geth"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "erigon",
+        repo: "erigontech/erigon",
+        source: AssetSource::GitHub,
+        tag_prefix: "v",
+        asset_template: "erigon_{version}_{os}_{goarch}.tar.gz",
+        layout: InstallLayout::Binaries(&["erigon"]),
+        archive: ArchiveFormat::TarGz,
+        java: None,
+    },
+    ClientInstaller {
+        name: "nethermind",
+        repo: "NethermindEth/nethermind",
+        source: AssetSource::GitHub,
+        tag_prefix: "",
+        asset_template: "nethermind-{version_num}-*-{dotnet_os}-{dotnet_arch}.zip",
+        layout: InstallLayout::Tree {
+            binary: "nethermind",
+            entrypoint: "nethermind",
+        },
+        archive: ArchiveFormat::Zip,
+        java: None,
+    },
+    ClientInstaller {
+        name: "besu",
+        repo: "hyperledger/besu",
+        source: AssetSource::GitHub,
+        tag_prefix: "",
+        asset_template: "besu-{version_num}.tar.gz",
+        layout: InstallLayout::Tree {
+            binary: "besu",
+            entrypoint: "bin/besu",
+        },
+        archive: ArchiveFormat::TarGz,
+        java: Some(21),
+    },
+];
+
+pub fn installer(name: &str) -> Result<&'static ClientInstaller, InstallError> {
+    INSTALLERS
         .iter()
-        .find(|a| a.name == release_name)
-        .map(|a| a.browser_download_url.clone())
-        .unwrap();
+        .find(|i| i.name == name)
+        .ok_or_else(|| InstallError::UnknownClient(name.to_string()))
+}
 
-    let tmp_dir = dirs::home_dir().unwrap().join(".ethup/tmp");
-    tokio::fs::create_dir_all(&tmp_dir).await?;
-    let tar_path = tmp_dir.join("reth.tar.gz");
+pub fn client_names() -> Vec<&'static str> {
+    INSTALLERS.iter().map(|i| i.name).collect()
+}
 
-    let response = get(download_url).await?;
-    let mut file = tokio::fs::File::create(&tar_path).await?;
+impl ClientInstaller {
+    pub fn binary(&self) -> &'static str {
+        match self.layout {
+            InstallLayout::Binaries(binaries) => binaries[0],
+            InstallLayout::Tree { binary, .. } => binary,
+        }
+    }
 
-    let mut stream = response.bytes_stream();
+    pub async fn release(
+        &self,
+        version: Option<&str>,
+        mirror: Option<&Url>,
+    ) -> anyhow::Result<Release> {
+        match mirror {
+            Some(base) => mirror_release(base, self.repo, version).await,
+            None => fetch_release(self.repo, version).await,
+        }
+    }
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        file.write_all(&chunk).await?;
+    pub fn changelog_url(&self, tag: &str) -> String {
+        format!("https://github.com/{}/releases/tag/{}", self.repo, tag)
     }
 
-    let tar_gz = File::open(tar_path)?;
-    let tar = GzDecoder::new(tar_gz);
-    let mut archive = Archive::new(tar);
+    // downloads, verifies and extracts a release without touching the
+    // installed client
+    pub async fn stage(
+        &self,
+        mut release: Release,
+        verify: &VerifySettings,
+    ) -> anyhow::Result<Staged> {
+        let expected = self.asset_name(&release.tag_name)?;
 
-    let bin_dir = bin_dir();
-    std::fs::create_dir_all(&bin_dir)?;
-    archive.unpack(&bin_dir)?;
+        // mirrors carry geth's archives next to the other clients'
+        if let AssetSource::GethStore = self.source
+            && release.assets.is_empty()
+        {
+            let prefix = expected.split('*').next().unwrap_or_default();
+            release.assets = gethstore_assets(prefix).await?;
+        }
 
-    let mut perms = metadata(bin_dir.join("reth"))?.permissions();
-    perms.set_mode(0o755);
-    set_permissions(bin_dir.join("reth"), perms)?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|a| glob_match(&expected, &a.name))
+            .ok_or_else(|| InstallError::NoMatchingAsset {
+                client: self.name.to_string(),
+                version: release.tag_name.clone(),
+                expected: expected.clone(),
+            })?;
 
-    Ok(())
+        self.stage_asset(&release, asset, verify).await
+    }
+
+    async fn stage_asset(
+        &self,
+        release: &Release,
+        asset: &Asset,
+        verify: &VerifySettings,
+    ) -> anyhow::Result<Staged> {
+        let tmp_dir = tmp_dir();
+        tokio::fs::create_dir_all(&tmp_dir).await?;
+        let archive_path = tmp_dir.join(&asset.name);
+
+        download(&asset.browser_download_url, &archive_path).await?;
+        verify_download(self.name, release, asset, &archive_path, verify).await?;
+
+        let staging = self.extract(&archive_path)?;
+        std::fs::remove_file(&archive_path)?;
+
+        let prepared = self.prepare(&staging).await;
+        if prepared.is_err() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        let (root, version) = prepared?;
+        clean_tmp(&tmp_dir);
+
+        Ok(Staged {
+            tag: release.tag_name.clone(),
+            root,
+            staging,
+            version,
+        })
+    }
+
+    // finds the client inside an extracted archive and checks that it runs,
+    // returning its root and `--version` output
+    async fn prepare(&self, staging: &Path) -> anyhow::Result<(PathBuf, String)> {
+        let (root, exe) = match self.layout {
+            InstallLayout::Binaries(binaries) => {
+                for &binary in binaries {
+                    let found =
+                        find_file(staging, binary)?.ok_or_else(|| InstallError::MissingBinary {
+                            client: self.name.to_string(),
+                            binary: binary.to_string(),
+                        })?;
+                    set_permissions(&found, std::fs::Permissions::from_mode(0o755))?;
+                    std::fs::rename(found, staging.join(binary))?;
+                }
+                (staging.to_path_buf(), staging.join(binaries[0]))
+            }
+            InstallLayout::Tree { entrypoint, .. } => {
+                let root = single_subdir(staging)?.unwrap_or_else(|| staging.to_path_buf());
+                let exe = root.join(entrypoint);
+                if !exe.is_file() {
+                    return Err(InstallError::MissingBinary {
+                        client: self.name.to_string(),
+                        binary: entrypoint.to_string(),
+                    }
+                    .into());
+                }
+                set_permissions(&exe, std::fs::Permissions::from_mode(0o755))?;
+                (root, exe)
+            }
+        };
+
+        if let Some(java) = self.java {
+            check_java(self.name, java)?;
+        }
+        let version = run_version(&exe)
+            .await
+            .map_err(|e| anyhow::anyhow!("{} release is not usable: {}", self.name, e))?;
+        Ok((root, version))
+    }
+
+    // moves a staged release into its version directory with a rename and
+    // makes it the current one
+    pub fn activate(&self, staged: &Staged) -> anyhow::Result<()> {
+        std::fs::create_dir_all(self.versions_root())?;
+
+        let dest = self.version_dir(&staged.tag);
+        let replaced = self.versions_root().join(format!(".{}.old", staged.tag));
+        if replaced.exists() {
+            std::fs::remove_dir_all(&replaced)?;
+        }
+        if dest.exists() {
+            std::fs::rename(&dest, &replaced)?;
+        }
+        std::fs::rename(&staged.root, &dest)?;
+
+        if replaced.exists() {
+            std::fs::remove_dir_all(&replaced)?;
+        }
+        if staged.staging.exists() {
+            std::fs::remove_dir_all(&staged.staging)?;
+        }
+
+        self.use_version(&staged.tag)
+    }
+
+    // switches back to `previous` and removes the version that failed
+    pub fn rollback(&self, failed: &str, previous: Option<&str>) -> anyhow::Result<()> {
+        match previous {
+            Some(previous) => self.use_version(previous)?,
+            None => self.unlink()?,
+        }
+        self.remove_version(failed)
+    }
+
+    // moves a client installed before versioned installs existed into
+    // versions_dir()
+    fn adopt_legacy(&self, tag: &str) -> anyhow::Result<()> {
+        let dest = self.version_dir(tag);
+        match self.layout {
+            InstallLayout::Binaries(binaries) => {
+                for &binary in binaries {
+                    let path = bin_dir().join(binary);
+                    if path.symlink_metadata().is_ok_and(|m| m.is_file()) {
+                        std::fs::create_dir_all(&dest)?;
+                        std::fs::rename(path, dest.join(binary))?;
+                    }
+                }
+            }
+            InstallLayout::Tree { .. } => {
+                let legacy = legacy_clients_dir().join(self.name);
+                if legacy.is_dir() {
+                    std::fs::create_dir_all(self.versions_root())?;
+                    std::fs::rename(legacy, &dest)?;
+                }
+            }
+        }
+
+        if dest.is_dir() {
+            self.use_version(tag)?;
+        }
+        Ok(())
+    }
+
+    // the executable of an install that predates versions_dir(), if any
+    fn legacy_exe(&self) -> Option<PathBuf> {
+        let exe = match self.layout {
+            InstallLayout::Binaries(binaries) => bin_dir().join(binaries[0]),
+            InstallLayout::Tree { entrypoint, .. } => {
+                legacy_clients_dir().join(self.name).join(entrypoint)
+            }
+        };
+        exe.symlink_metadata()
+            .is_ok_and(|m| m.is_file())
+            .then_some(exe)
+    }
+
+    pub fn versions_root(&self) -> PathBuf {
+        versions_dir().join(self.name)
+    }
+
+    pub fn version_dir(&self, tag: &str) -> PathBuf {
+        self.versions_root().join(tag)
+    }
+
+    pub fn installed_versions(&self) -> anyhow::Result<Vec<String>> {
+        let root = self.versions_root();
+        if !root.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        for entry in std::fs::read_dir(root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                versions.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        versions.sort_by_key(|v| version_key(v));
+        Ok(versions)
+    }
+
+    pub fn current_version(&self) -> Option<String> {
+        let target = std::fs::read_link(self.versions_root().join("current")).ok()?;
+        Some(target.to_string_lossy().into_owned())
+    }
+
+    // repoints `current` in one rename and makes sure bin_dir() links
+    // through it
+    pub fn use_version(&self, tag: &str) -> anyhow::Result<()> {
+        if !self.version_dir(tag).is_dir() {
+            anyhow::bail!("{} {} is not installed", self.name, tag);
+        }
+
+        let root = self.versions_root();
+        let tmp = root.join("current.tmp");
+        if tmp.symlink_metadata().is_ok() {
+            std::fs::remove_file(&tmp)?;
+        }
+        std::os::unix::fs::symlink(tag, &tmp)?;
+        std::fs::rename(&tmp, root.join("current"))?;
+
+        let bin_dir = bin_dir();
+        std::fs::create_dir_all(&bin_dir)?;
+        for (binary, target) in self.links() {
+            let link = bin_dir.join(binary);
+            let target = root.join("current").join(target);
+            if std::fs::read_link(&link).is_ok_and(|t| t == target) {
+                continue;
+            }
+            if link.symlink_metadata().is_ok() {
+                std::fs::remove_file(&link)?;
+            }
+            std::os::unix::fs::symlink(target, link)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_version(&self, tag: &str) -> anyhow::Result<()> {
+        let dir = self.version_dir(tag);
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+
+    // removes the `current` symlink and the links in bin_dir()
+    pub fn unlink(&self) -> anyhow::Result<()> {
+        let current = self.versions_root().join("current");
+        if current.symlink_metadata().is_ok() {
+            std::fs::remove_file(current)?;
+        }
+        for (binary, _) in self.links() {
+            let link = bin_dir().join(binary);
+            if link.symlink_metadata().is_ok() {
+                std::fs::remove_file(link)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn links(&self) -> Vec<(&'static str, &'static str)> {
+        match self.layout {
+            InstallLayout::Binaries(binaries) => binaries.iter().map(|&b| (b, b)).collect(),
+            InstallLayout::Tree { binary, entrypoint } => vec![(binary, entrypoint)],
+        }
+    }
+
+    // runs `<binary> --version` to catch releases that don't start at all
+    pub async fn smoke_check(&self) -> anyhow::Result<String> {
+        run_version(&bin_dir().join(self.binary())).await
+    }
+
+    // reads the version back out of an archive name, e.g. v1.3.12 out of
+    // reth-v1.3.12-x86_64-unknown-linux-gnu.tar.gz
+    fn version_from_asset(&self, name: &str) -> Option<String> {
+        let template = self.asset_name("{version}").ok()?;
+        let pattern = regex::escape(&template)
+            .replace(r"\{version\}", "(v?[0-9][0-9A-Za-z.+-]*?)")
+            .replace(r"\*", ".*");
+        let caps = regex::Regex::new(&format!("^{}$", pattern))
+            .ok()?
+            .captures(name)?;
+
+        Some(self.normalize_version(caps.get(1)?.as_str()))
+    }
+
+    pub fn normalize_version(&self, version: &str) -> String {
+        format!("{}{}", self.tag_prefix, version.trim_start_matches('v'))
+    }
+
+    fn asset_name(&self, tag: &str) -> Result<String, InstallError> {
+        self.asset_name_for(tag, std::env::consts::OS, std::env::consts::ARCH)
+    }
+
+    fn asset_name_for(&self, tag: &str, host_os: &str, arch: &str) -> Result<String, InstallError> {
+        let unsupported = || InstallError::UnsupportedPlatform {
+            os: host_os.to_string(),
+            arch: arch.to_string(),
+        };
+
+        let (os, target) = match host_os {
+            "macos" => ("darwin", "apple-darwin"),
+            "linux" => ("linux", "unknown-linux-gnu"),
+            _ => return Err(unsupported()),
+        };
+        let (goarch, dotnet_arch) = match arch {
+            "x86_64" => ("amd64", "x64"),
+            "aarch64" => ("arm64", "arm64"),
+            _ => return Err(unsupported()),
+        };
+        let dotnet_os = if os == "darwin" { "macos" } else { os };
+        let nimbus_os = if os == "darwin" { "macOS" } else { "Linux" };
+
+        Ok(self
+            .asset_template
+            .replace("{version_num}", tag.trim_start_matches('v'))
+            .replace("{version}", tag)
+            .replace("{os}", os)
+            .replace("{arch}", arch)
+            .replace("{goarch}", goarch)
+            .replace("{target}", target)
+            .replace("{dotnet_os}", dotnet_os)
+            .replace("{dotnet_arch}", dotnet_arch)
+            .replace("{nimbus_os}", nimbus_os))
+    }
+
+    fn extract(&self, archive_path: &Path) -> anyhow::Result<PathBuf> {
+        let staging = archive_path.with_extension("extract");
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::create_dir_all(&staging)?;
+
+        match self.archive {
+            ArchiveFormat::TarGz => {
+                Archive::new(GzDecoder::new(File::open(archive_path)?)).unpack(&staging)?;
+            }
+            ArchiveFormat::Zip => {
+                zip::ZipArchive::new(File::open(archive_path)?)?.extract(&staging)?;
+            }
+            ArchiveFormat::Raw => {
+                // the asset is the binary itself
+                if let InstallLayout::Binaries(&[binary]) = self.layout {
+                    std::fs::copy(archive_path, staging.join(binary))?;
+                }
+            }
+        }
+
+        Ok(staging)
+    }
+}
+
+pub struct Staged {
+    pub tag: String,
+    pub version: String,
+    root: PathBuf,
+    staging: PathBuf,
+}
+
+async fn run_version(exe: &Path) -> anyhow::Result<String> {
+    let meta = std::fs::metadata(exe).map_err(|e| anyhow::anyhow!("{}: {}", exe.display(), e))?;
+    if !meta.is_file() || meta.permissions().mode() & 0o111 == 0 {
+        anyhow::bail!("{} is not an executable file", exe.display());
+    }
+
+    let output = tokio::time::timeout(
+        SMOKE_CHECK_TIMEOUT,
+        tokio::process::Command::new(exe)
+            .arg("--version")
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("{} --version timed out", exe.display()))??;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} --version failed ({}): {}",
+            exe.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+// removes leftovers of earlier installs: partial downloads that were never
+// resumed and staging directories of interrupted extracts
+fn clean_tmp(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > STALE_TMP_AGE);
+        if !stale {
+            continue;
+        }
+
+        let _ = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+    }
+}
+
+fn find_file(dir: &Path, name: &str) -> std::io::Result<Option<PathBuf>> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if let Some(found) = find_file(&path, name)? {
+                return Ok(Some(found));
+            }
+        } else if path.file_name().is_some_and(|n| n == name) {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
 }
 
-pub async fn download_lighthouse() -> anyhow::Result<()> {
-    let client = reqwest::Client::new();
+fn single_subdir(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
 
-    let release: Release = client
-        .get("https://api.github.com/repos/sigp/lighthouse/releases/latest")
+    match entries.as_slice() {
+        [only] if only.is_dir() => Ok(Some(only.clone())),
+        _ => Ok(None),
+    }
+}
+
+async fn fetch_release(repo: &str, version: Option<&str>) -> anyhow::Result<Release> {
+    let url = match version {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            repo, tag
+        ),
+        None => format!("https://api.github.com/repos/{}/releases/latest", repo),
+    };
+
+    let resp = reqwest::Client::new()
+        .get(&url)
         .header("User-Agent", "ethup")
         .send()
-        .await?
-        .json()
         .await?;
 
-    let target_os = match std::env::consts::OS {
-        "macos" => "apple-darwin",
-        "linux" => "unknown-linux-gnu",
-        "windows" => "pc-windows-gnu",
-        _ => panic!("unsupported OS"),
-    };
+    // unauthenticated API calls are limited per hour, and GitHub answers
+    // with 403 or 429 once they are used up
+    let status = resp.status().as_u16();
+    if status == 403 || status == 429 {
+        return Err(InstallError::RateLimited {
+            repo: repo.to_string(),
+            status,
+        }
+        .into());
+    }
+    if !resp.status().is_success() {
+        return Err(InstallError::ReleaseNotFound {
+            repo: repo.to_string(),
+            version: version.unwrap_or("latest").to_string(),
+            status,
+        }
+        .into());
+    }
+
+    Ok(resp.json().await?)
+}
 
-    let release_name = format!(
-        "lighthouse-{}-{}-{}.tar.gz",
-        release.tag_name,
-        std::env::consts::ARCH,
-        target_os
+async fn gethstore_assets(prefix: &str) -> anyhow::Result<Vec<Asset>> {
+    let url = format!(
+        "{}?restype=container&comp=list&prefix={}",
+        GETHSTORE_URL, prefix
     );
+    let body = reqwest::get(&url).await?.error_for_status()?.text().await?;
+
+    let mut assets = Vec::new();
+    for blob in body.split("<Blob>").skip(1) {
+        let Some(name) = xml_field(blob, "Name") else {
+            continue;
+        };
+
+        // azure reports a base64 md5 of each blob, the only digest geth publishes
+        let digest = xml_field(blob, "Content-MD5")
+            .and_then(|b64| BASE64.decode(b64).ok())
+            .map(|md5| {
+                let hex: String = md5.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("md5:{}", hex)
+            });
+
+        assets.push(Asset {
+            browser_download_url: Url::parse(&format!("{}/{}", GETHSTORE_URL, name))?,
+            name: name.to_string(),
+            digest,
+        });
+    }
+
+    Ok(assets)
+}
+
+fn xml_field<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(&xml[start..end])
+}
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.is_empty()
+}
+
+pub async fn install(
+    client: &str,
+    version: Option<&str>,
+    settings: &Settings,
+) -> anyhow::Result<String> {
+    let installer = installer(client)?;
+    let version = version.map(|v| installer.normalize_version(v));
+    let mirror = settings.mirror()?;
 
-    let download_url = release
+    let release = installer
+        .release(version.as_deref(), mirror.as_ref())
+        .await?;
+    let staged = installer.stage(release, &settings.verify).await?;
+    installer.activate(&staged)?;
+
+    Manifest::record(client, &staged.tag)?;
+    println!("Installed {} {} ({})", client, staged.tag, staged.version);
+
+    Ok(staged.tag)
+}
+
+// installs from an archive on disk; checksum and signature files are picked
+// up from the same directory, as if it were a release
+pub async fn install_from(
+    client: &str,
+    version: Option<&str>,
+    archive: &Path,
+    settings: &Settings,
+) -> anyhow::Result<String> {
+    let installer = installer(client)?;
+    let archive = std::path::absolute(archive)?;
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let tag = match version {
+        Some(v) => installer.normalize_version(v),
+        None => installer.version_from_asset(&name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "cannot tell the {} version from {}; pass it as {}@<version>",
+                client,
+                name,
+                client
+            )
+        })?,
+    };
+
+    let dir = archive.parent().unwrap_or(Path::new("/"));
+    let dir_url = Url::from_directory_path(dir)
+        .map_err(|_| anyhow::anyhow!("invalid archive path {}", archive.display()))?;
+    let release = Release {
+        tag_name: tag,
+        assets: list_assets(&dir_url).await?,
+    };
+    let asset = release
         .assets
         .iter()
-        .find(|a| a.name == release_name)
-        .map(|a| a.browser_download_url.clone())
-        .unwrap();
+        .find(|a| a.name == name)
+        .ok_or_else(|| anyhow::anyhow!("{} not found", archive.display()))?;
+
+    let staged = installer
+        .stage_asset(&release, asset, &settings.verify)
+        .await?;
+    installer.activate(&staged)?;
 
-    let tmp_dir = dirs::home_dir().unwrap().join(".ethup/tmp");
-    tokio::fs::create_dir_all(&tmp_dir).await?;
-    let tar_path = tmp_dir.join("lighthouse.tar.gz");
+    Manifest::record(client, &staged.tag)?;
+    println!(
+        "Installed {} {} ({}) from {}",
+        client,
+        staged.tag,
+        staged.version,
+        archive.display()
+    );
 
-    let response = get(download_url).await?;
-    let mut file = tokio::fs::File::create(&tar_path).await?;
+    Ok(staged.tag)
+}
 
-    let mut stream = response.bytes_stream();
+// moves installs from before versions_dir() into it, including binaries
+// dropped straight into bin_dir() before there was a manifest
+pub async fn migrate_legacy_installs() -> anyhow::Result<()> {
+    let manifest = Manifest::load()?;
+    // e.g. "reth Version: 1.3.12" or "Lighthouse v7.0.1-e42406d"
+    let semver = regex::Regex::new(r"[0-9]+\.[0-9]+\.[0-9]+")?;
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        file.write_all(&chunk).await?;
+    for installer in INSTALLERS {
+        if installer.current_version().is_some() {
+            continue;
+        }
+
+        let tag = match manifest.version(installer.name) {
+            Some(version) => version.to_string(),
+            // installed before the manifest existed, so ask the binary
+            None => {
+                let Some(exe) = installer.legacy_exe() else {
+                    continue;
+                };
+                let output = run_version(&exe).await.unwrap_or_default();
+                semver.find(&output).map_or("unknown".to_string(), |m| {
+                    installer.normalize_version(m.as_str())
+                })
+            }
+        };
+
+        installer.adopt_legacy(&tag)?;
+        if manifest.version(installer.name).is_none() && installer.current_version().is_some() {
+            Manifest::record(installer.name, &tag)?;
+        }
     }
+    Ok(())
+}
 
-    let tar_gz = File::open(tar_path)?;
-    let tar = GzDecoder::new(tar_gz);
-    let mut archive = Archive::new(tar);
+pub async fn ensure_clients(clients: &[&str], settings: &Settings) -> anyhow::Result<()> {
+    migrate_legacy_installs().await?;
+    let manifest = Manifest::load()?;
 
-    let bin_dir = bin_dir();
-    std::fs::create_dir_all(&bin_dir)?;
-    archive.unpack(&bin_dir)?;
+    for &client in clients {
+        let installer = installer(client)?;
+        let installed = bin_dir().join(installer.binary()).exists();
 
-    let mut perms = metadata(bin_dir.join("lighthouse"))?.permissions();
-    perms.set_mode(0o755);
-    set_permissions(bin_dir.join("lighthouse"), perms)?;
+        match settings
+            .versions
+            .get(client)
+            .map(|v| installer.normalize_version(v))
+        {
+            Some(pin) if !installed || manifest.version(client) != Some(pin.as_str()) => {
+                if installer.version_dir(&pin).is_dir() {
+                    println!("Switching {} to pinned {}", client, pin);
+                    installer.use_version(&pin)?;
+                    Manifest::record(client, &pin)?;
+                } else {
+                    println!("Installing pinned {} {}", client, pin);
+                    install(client, Some(&pin), settings).await?;
+                }
+            }
+            None if !installed => {
+                install(client, None, settings).await?;
+            }
+            _ => {}
+        }
+    }
 
     Ok(())
 }
@@ -170,3 +1008,406 @@ pub async fn ensure_jwt() -> anyhow::Result<PathBuf> {
 
     Ok(jwt_path)
 }
+
+pub async fn fetch_text(url: &Url) -> anyhow::Result<String> {
+    if url.scheme() == "file" {
+        return Ok(tokio::fs::read_to_string(file_path(url)?).await?);
+    }
+
+    let resp = reqwest::Client::new()
+        .get(url.clone())
+        .header("User-Agent", "ethup")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(resp.text().await?)
+}
+
+// a mirror is laid out like GitHub release downloads:
+// <base>/<owner>/<repo>/releases/download/<tag>/<asset>
+async fn mirror_release(base: &Url, repo: &str, version: Option<&str>) -> anyhow::Result<Release> {
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    let releases = base.join(&format!("{}/releases/download/", repo))?;
+
+    let tag = match version {
+        Some(tag) => tag.to_string(),
+        None => list_dir(&releases)
+            .await?
+            .into_iter()
+            .filter(|(_, is_dir)| *is_dir)
+            .map(|(name, _)| name)
+            // like /releases/latest, only an explicit version picks a
+            // pre-release
+            .filter(|tag| !is_prerelease(tag))
+            .max_by_key(|tag| version_key(tag))
+            .ok_or_else(|| anyhow::anyhow!("mirror has no releases at {}", releases))?,
+    };
+
+    let assets = list_assets(&releases.join(&format!("{}/", tag))?)
+        .await
+        .map_err(|_| InstallError::ReleaseNotFound {
+            repo: format!("{} (mirror {})", repo, base),
+            version: tag.clone(),
+            status: 404,
+        })?;
+
+    Ok(Release {
+        tag_name: tag,
+        assets,
+    })
+}
+
+async fn list_assets(dir: &Url) -> anyhow::Result<Vec<Asset>> {
+    list_dir(dir)
+        .await?
+        .into_iter()
+        .filter(|(_, is_dir)| !is_dir)
+        .map(|(name, _)| {
+            Ok(Asset {
+                browser_download_url: dir.join(&name)?,
+                name,
+                digest: None,
+            })
+        })
+        .collect()
+}
+
+// lists a local directory, or an http directory index as served by nginx,
+// apache or `python -m http.server`
+async fn list_dir(dir: &Url) -> anyhow::Result<Vec<(String, bool)>> {
+    if dir.scheme() == "file" {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(file_path(dir)?)? {
+            let entry = entry?;
+            let is_dir = entry.path().is_dir();
+            entries.push((entry.file_name().to_string_lossy().into_owned(), is_dir));
+        }
+        return Ok(entries);
+    }
+
+    let index = fetch_text(dir).await?;
+    let href = regex::Regex::new(r#"href="([^"?#]+)""#)?;
+
+    Ok(href
+        .captures_iter(&index)
+        .filter_map(|c| {
+            let link = c.get(1)?.as_str();
+            let name = link.trim_end_matches('/');
+            if name.is_empty() || name.contains('/') || name.starts_with('.') {
+                return None;
+            }
+            Some((name.to_string(), link.ends_with('/')))
+        })
+        .collect())
+}
+
+fn is_prerelease(tag: &str) -> bool {
+    tag.contains('-')
+}
+
+// orders tags numerically, with a pre-release before the release it leads up
+// to: v1.0.0-rc.1 < v1.0.0 < v1.0.1
+fn version_key(tag: &str) -> Vec<u64> {
+    let numbers = |s: &str| -> Vec<u64> {
+        s.split(|c: char| !c.is_ascii_digit())
+            .filter_map(|n| n.parse().ok())
+            .collect()
+    };
+
+    // everything after the first '-' is the pre-release, see is_prerelease
+    let (core, pre) = match tag.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (tag, None),
+    };
+    let mut key = numbers(core);
+    key.resize(key.len().max(4), 0);
+    match pre {
+        Some(pre) => {
+            key.push(0);
+            key.extend(numbers(pre));
+        }
+        None => key.push(1),
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // asset names as published by each project
+    const ASSETS: &[(&str, &str, &str, &str, &str)] = &[
+        (
+            "reth",
+            "v1.3.12",
+            "linux",
+            "x86_64",
+            "reth-v1.3.12-x86_64-unknown-linux-gnu.tar.gz",
+        ),
+        (
+            "reth",
+            "v1.3.12",
+            "macos",
+            "aarch64",
+            "reth-v1.3.12-aarch64-apple-darwin.tar.gz",
+        ),
+        (
+            "lighthouse",
+            "v7.0.1",
+            "linux",
+            "aarch64",
+            "lighthouse-v7.0.1-aarch64-unknown-linux-gnu.tar.gz",
+        ),
+        ("teku", "25.4.1", "linux", "x86_64", "teku-25.4.1.tar.gz"),
+        (
+            "prysm",
+            "v6.0.0",
+            "linux",
+            "x86_64",
+            "beacon-chain-v6.0.0-linux-amd64",
+        ),
+        (
+            "prysm",
+            "v6.0.0",
+            "macos",
+            "aarch64",
+            "beacon-chain-v6.0.0-darwin-arm64",
+        ),
+        (
+            "nimbus",
+            "v25.4.1",
+            "linux",
+            "x86_64",
+            "nimbus-eth2_Linux_amd64_25.4.1_6e8bd9b3.tar.gz",
+        ),
+        (
+            "nimbus",
+            "v25.4.1",
+            "macos",
+            "aarch64",
+            "nimbus-eth2_macOS_arm64_25.4.1_6e8bd9b3.tar.gz",
+        ),
+        (
+            "lodestar",
+            "v1.29.0",
+            "linux",
+            "x86_64",
+            "lodestar-v1.29.0-linux-amd64.tar.gz",
+        ),
+        (
+            "grandine",
+            "1.1.0",
+            "linux",
+            "x86_64",
+            "grandine-1.1.0-linux-amd64",
+        ),
+        (
+            "geth",
+            "v1.15.11",
+            "linux",
+            "x86_64",
+            "geth-linux-amd64-1.15.11-36b2371c.tar.gz",
+        ),
+        (
+            "geth",
+            "v1.15.11",
+            "macos",
+            "aarch64",
+            "geth-darwin-arm64-1.15.11-36b2371c.tar.gz",
+        ),
+        (
+            "erigon",
+            "v3.0.3",
+            "linux",
+            "x86_64",
+            "erigon_v3.0.3_linux_amd64.tar.gz",
+        ),
+        (
+            "nethermind",
+            "1.31.10",
+            "linux",
+            "x86_64",
+            "nethermind-1.31.10-2a6cdc5d-linux-x64.zip",
+        ),
+        (
+            "nethermind",
+            "1.31.10",
+            "macos",
+            "aarch64",
+            "nethermind-1.31.10-2a6cdc5d-macos-arm64.zip",
+        ),
+        ("besu", "25.4.1", "linux", "x86_64", "besu-25.4.1.tar.gz"),
+    ];
+
+    #[test]
+    fn templates_match_published_assets() {
+        for &(client, tag, os, arch, asset) in ASSETS {
+            let pattern = installer(client)
+                .unwrap()
+                .asset_name_for(tag, os, arch)
+                .unwrap();
+            assert!(
+                glob_match(&pattern, asset),
+                "{} did not match {}",
+                pattern,
+                asset
+            );
+        }
+    }
+
+    #[test]
+    fn templates_skip_other_platforms_and_side_files() {
+        let geth = installer("geth").unwrap();
+        let pattern = geth.asset_name_for("v1.15.11", "linux", "x86_64").unwrap();
+        assert!(!glob_match(
+            &pattern,
+            "geth-linux-amd64-1.15.11-36b2371c.tar.gz.asc"
+        ));
+        assert!(!glob_match(
+            &pattern,
+            "geth-linux-arm64-1.15.11-36b2371c.tar.gz"
+        ));
+        assert!(!glob_match(
+            &pattern,
+            "geth-alltools-linux-amd64-1.15.11-36b2371c.tar.gz"
+        ));
+        assert!(!glob_match(
+            &pattern,
+            "geth-linux-amd64-1.15.1-36b2371c.tar.gz"
+        ));
+
+        let nimbus = installer("nimbus").unwrap();
+        let pattern = nimbus.asset_name_for("v25.4.1", "linux", "x86_64").unwrap();
+        assert!(!glob_match(
+            &pattern,
+            "nimbus-eth2_Linux_arm64_25.4.1_6e8bd9b3.tar.gz"
+        ));
+        assert!(!glob_match(
+            &pattern,
+            "nimbus-eth2_Linux_amd64_25.4.1_6e8bd9b3.tar.gz.sha256sum"
+        ));
+
+        let reth = installer("reth").unwrap();
+        let pattern = reth.asset_name_for("v1.3.12", "linux", "x86_64").unwrap();
+        assert!(!glob_match(
+            &pattern,
+            "reth-v1.3.12-x86_64-unknown-linux-gnu.tar.gz.asc"
+        ));
+    }
+
+    #[test]
+    fn unsupported_platforms_are_rejected() {
+        let reth = installer("reth").unwrap();
+        assert!(reth.asset_name_for("v1.3.12", "windows", "x86_64").is_err());
+        assert!(reth.asset_name_for("v1.3.12", "linux", "riscv64").is_err());
+    }
+
+    #[test]
+    fn glob_match_edge_cases() {
+        assert!(glob_match("a*", "a"));
+        assert!(glob_match("*b", "ab"));
+        assert!(glob_match("a*b*c", "a-b-c"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exact.asc"));
+        assert!(!glob_match("a*bc", "abc-bd"));
+        assert!(!glob_match("ab*b", "ab"));
+    }
+
+    // what `eth install --from <archive>` reads the version out of
+    #[test]
+    fn version_from_asset_reads_the_tag() {
+        for &(client, tag, _, _, _) in ASSETS {
+            let installer = installer(client).unwrap();
+            let Ok(name) = installer.asset_name(tag) else {
+                continue;
+            };
+            let name = name.replace('*', "6e8bd9b3");
+            assert_eq!(
+                installer.version_from_asset(&name).as_deref(),
+                Some(tag),
+                "{}",
+                name
+            );
+        }
+
+        let reth = installer("reth").unwrap();
+        assert_eq!(reth.version_from_asset("lighthouse-v7.0.1.tar.gz"), None);
+        assert_eq!(reth.version_from_asset("reth.tar.gz"), None);
+    }
+
+    #[test]
+    fn version_from_asset_keeps_pre_releases() {
+        let reth = installer("reth").unwrap();
+        let name = reth.asset_name("v1.4.0-rc.1").unwrap();
+        assert_eq!(
+            reth.version_from_asset(&name).as_deref(),
+            Some("v1.4.0-rc.1")
+        );
+    }
+
+    #[test]
+    fn normalize_version_applies_the_tag_prefix() {
+        assert_eq!(
+            installer("reth").unwrap().normalize_version("1.3.12"),
+            "v1.3.12"
+        );
+        assert_eq!(
+            installer("reth").unwrap().normalize_version("v1.3.12"),
+            "v1.3.12"
+        );
+        assert_eq!(
+            installer("teku").unwrap().normalize_version("v25.4.1"),
+            "25.4.1"
+        );
+    }
+
+    #[test]
+    fn version_key_orders_releases() {
+        let mut tags = vec![
+            "v1.10.0",
+            "v1.9.0",
+            "v1.10.0-rc.2",
+            "v1.10.0-rc.10",
+            "v1.9.1",
+        ];
+        tags.sort_by_key(|t| version_key(t));
+        assert_eq!(
+            tags,
+            [
+                "v1.9.0",
+                "v1.9.1",
+                "v1.10.0-rc.2",
+                "v1.10.0-rc.10",
+                "v1.10.0"
+            ]
+        );
+        assert_eq!(version_key("v1.9"), version_key("v1.9.0"));
+        assert!(version_key("25.4.1") < version_key("25.10.0"));
+    }
+
+    #[tokio::test]
+    async fn mirror_latest_skips_pre_releases() {
+        let dir = tempfile::tempdir().unwrap();
+        let releases = dir.path().join("paradigmxyz/reth/releases/download");
+        for tag in ["v1.9.0", "v1.10.0", "v1.11.0-rc.1"] {
+            std::fs::create_dir_all(releases.join(tag)).unwrap();
+            std::fs::write(releases.join(tag).join("reth.tar.gz"), "").unwrap();
+        }
+        let base = Url::from_directory_path(dir.path()).unwrap();
+
+        let latest = mirror_release(&base, "paradigmxyz/reth", None)
+            .await
+            .unwrap();
+        assert_eq!(latest.tag_name, "v1.10.0");
+
+        let pinned = mirror_release(&base, "paradigmxyz/reth", Some("v1.11.0-rc.1"))
+            .await
+            .unwrap();
+        assert_eq!(pinned.tag_name, "v1.11.0-rc.1");
+        assert_eq!(pinned.assets.len(), 1);
+    }
+}
@@ -12,6 +12,44 @@ pub fn data_dir() -> PathBuf {
     dirs::home_dir().unwrap().join(".ethup/data")
 }
 
-pub fn log_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".ethup/logs")
+// each chain runs its own node, so logs and state are kept per chain
+pub fn log_dir(chain: &str) -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/logs").join(chain)
+}
+
+pub fn config_file() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/config.toml")
+}
+
+pub fn state_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/state")
+}
+
+pub fn state_file(chain: &str) -> PathBuf {
+    state_dir().join(format!("{}.json", chain))
+}
+
+pub fn manifest_file() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/manifest.json")
+}
+
+pub fn keys_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/keys")
+}
+
+pub fn gnupg_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/gnupg")
+}
+
+// where tree installs lived before versions_dir()
+pub fn legacy_clients_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/clients")
+}
+
+pub fn versions_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/versions")
+}
+
+pub fn tmp_dir() -> PathBuf {
+    dirs::home_dir().unwrap().join(".ethup/tmp")
 }
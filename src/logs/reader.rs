@@ -0,0 +1,190 @@
+use flate2::read::GzDecoder;
+use regex::Regex;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::writer::log_path;
+use crate::runner::format_line;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct Query {
+    // None shows everything that matches
+    pub lines: Option<usize>,
+    pub grep: Option<Regex>,
+    pub since: Option<SystemTime>,
+}
+
+struct Entry {
+    ts: SystemTime,
+    prefix: &'static str,
+    color: &'static str,
+    text: String,
+}
+
+struct Source {
+    prefix: &'static str,
+    color: &'static str,
+    path: PathBuf,
+    offset: u64,
+    last_ts: SystemTime,
+}
+
+impl Source {
+    fn new(chain: &str, prefix: &'static str, color: &'static str) -> Self {
+        Self {
+            prefix,
+            color,
+            path: log_path(chain, prefix),
+            offset: 0,
+            last_ts: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    // also returns the first timestamp in `raw`, before any filtering, so
+    // callers know how far back the text goes
+    fn parse(&mut self, raw: &str, query: &Query) -> (Vec<Entry>, Option<SystemTime>) {
+        let mut entries = Vec::new();
+        let mut first = None;
+
+        for line in raw.lines() {
+            let (ts, text) = match line.split_once(' ') {
+                Some((ts, text)) => match humantime::parse_rfc3339(ts) {
+                    Ok(ts) => {
+                        first.get_or_insert(ts);
+                        (ts, text)
+                    }
+                    Err(_) => (self.last_ts, line),
+                },
+                None => (self.last_ts, line),
+            };
+            self.last_ts = ts;
+
+            if query.since.is_some_and(|since| ts < since) {
+                continue;
+            }
+            if query.grep.as_ref().is_some_and(|re| !re.is_match(text)) {
+                continue;
+            }
+
+            entries.push(Entry {
+                ts,
+                prefix: self.prefix,
+                color: self.color,
+                text: text.to_string(),
+            });
+        }
+
+        (entries, first)
+    }
+
+    fn read_history(&mut self, query: &Query, keep: usize) -> io::Result<Vec<Entry>> {
+        let mut entries = Vec::new();
+        let mut oldest = None;
+
+        if self.path.exists() {
+            let raw = std::fs::read(&self.path)?;
+            self.offset = raw.len() as u64;
+            (entries, oldest) = self.parse(&String::from_utf8_lossy(&raw), query);
+        }
+
+        for index in 1..=keep {
+            // archives only hold older lines, so once a file starts before
+            // --since there is nothing left to find
+            let enough = match (query.since, query.lines) {
+                (Some(since), _) => oldest.is_some_and(|ts| ts <= since),
+                (None, Some(lines)) => entries.len() >= lines,
+                (None, None) => false,
+            };
+            let archive = self.path.with_extension(format!("log.{}.gz", index));
+            if enough || !archive.exists() {
+                break;
+            }
+
+            let mut raw = String::new();
+            GzDecoder::new(File::open(archive)?).read_to_string(&mut raw)?;
+            let (mut older, first) = self.parse(&raw, query);
+            older.append(&mut entries);
+            entries = older;
+            oldest = first.or(oldest);
+        }
+
+        Ok(entries)
+    }
+
+    fn read_new(&mut self, query: &Query) -> io::Result<Vec<Entry>> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Ok(Vec::new());
+        };
+
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // the log was rotated underneath us, start over on the new file
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut raw = Vec::new();
+        file.take(len - self.offset).read_to_end(&mut raw)?;
+
+        let Some(end) = raw.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        self.offset += end as u64 + 1;
+
+        Ok(self.parse(&String::from_utf8_lossy(&raw[..=end]), query).0)
+    }
+}
+
+pub async fn show(
+    chain: &str,
+    clients: &[(&'static str, &'static str)],
+    query: Query,
+    follow: bool,
+    keep: usize,
+) -> anyhow::Result<()> {
+    let mut sources: Vec<Source> = clients
+        .iter()
+        .map(|&(prefix, color)| Source::new(chain, prefix, color))
+        .collect();
+
+    let mut entries = Vec::new();
+    for source in sources.iter_mut() {
+        entries.extend(source.read_history(&query, keep)?);
+    }
+
+    entries.sort_by_key(|e| e.ts);
+    let skip = query
+        .lines
+        .map_or(0, |lines| entries.len().saturating_sub(lines));
+    print_entries(&entries[skip..]);
+
+    if !follow {
+        return Ok(());
+    }
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let mut entries = Vec::new();
+        for source in sources.iter_mut() {
+            entries.extend(source.read_new(&query)?);
+        }
+
+        entries.sort_by_key(|e| e.ts);
+        print_entries(&entries);
+    }
+}
+
+fn print_entries(entries: &[Entry]) {
+    for e in entries {
+        let ts = humantime::format_rfc3339_seconds(e.ts);
+        let line = format!("{} {}", ts, e.text);
+        println!("{}", format_line(e.prefix, e.color, &line));
+    }
+}
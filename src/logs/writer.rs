@@ -0,0 +1,252 @@
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+
+use crate::layout::log_dir;
+use crate::settings::LogSettings;
+
+pub struct RotatingLog {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingLog {
+    pub fn open(path: PathBuf, settings: &LogSettings) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            max_bytes: settings.max_size_mb * 1024 * 1024,
+            keep: settings.keep,
+            file,
+            written,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let ts = humantime::format_rfc3339_millis(SystemTime::now());
+        let entry = format!("{} {}\n", ts, line);
+
+        self.file.write_all(entry.as_bytes())?;
+        self.written += entry.len() as u64;
+
+        if self.max_bytes > 0 && self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let _ = std::fs::remove_file(self.archive_path(self.keep));
+        for i in (1..self.keep).rev() {
+            let from = self.archive_path(i);
+            if from.exists() {
+                std::fs::rename(&from, self.archive_path(i + 1))?;
+            }
+        }
+
+        let rotated = self.path.with_extension("log.1");
+        std::fs::rename(&self.path, &rotated)?;
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+
+        compress(&rotated, &self.archive_path(1))?;
+        std::fs::remove_file(rotated)?;
+
+        Ok(())
+    }
+
+    fn archive_path(&self, index: usize) -> PathBuf {
+        self.path.with_extension(format!("log.{}.gz", index))
+    }
+}
+
+fn compress(src: &Path, dst: &Path) -> io::Result<()> {
+    let mut input = File::open(src)?;
+    let mut encoder = GzEncoder::new(File::create(dst)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+pub fn log_path(chain: &str, role: &str) -> PathBuf {
+    log_dir(chain).join(format!("{}.log", role.to_lowercase()))
+}
+
+pub fn spawn_writer(
+    chain: &str,
+    role: &str,
+    settings: &LogSettings,
+) -> io::Result<mpsc::UnboundedSender<String>> {
+    let mut log = RotatingLog::open(log_path(chain, role), settings)?;
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+
+    tokio::task::spawn_blocking(move || {
+        while let Some(line) = rx.blocking_recv() {
+            if let Err(e) = log.write_line(&line) {
+                eprintln!("failed to write {}: {}", log.path.display(), e);
+            }
+        }
+    });
+
+    Ok(tx)
+}
+
+pub async fn forward_lines<R>(reader: R, tx: mpsc::UnboundedSender<String>)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+
+    // keeps draining the pipe even if the writer is gone, so the client
+    // never blocks on a full pipe
+    while let Some(line) = next_line(&mut reader, &mut buf).await {
+        let _ = tx.send(line);
+    }
+}
+
+// reads one line, replacing invalid UTF-8 instead of giving up on it;
+// None only at the end of the output
+pub async fn next_line<R>(reader: &mut R, buf: &mut Vec<u8>) -> Option<String>
+where
+    R: AsyncBufRead + Unpin,
+{
+    buf.clear();
+    match reader.read_until(b'\n', buf).await {
+        Ok(0) | Err(_) => None,
+        Ok(_) => {
+            let line = buf.strip_suffix(b"\n").unwrap_or(buf);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            Some(String::from_utf8_lossy(line).into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn open(dir: &Path, keep: usize) -> RotatingLog {
+        let settings = LogSettings {
+            keep,
+            ..Default::default()
+        };
+        let mut log = RotatingLog::open(dir.join("el.log"), &settings).unwrap();
+        // rotate after every line
+        log.max_bytes = 1;
+        log
+    }
+
+    fn archive(dir: &Path, index: usize) -> Option<String> {
+        let path = dir.join(format!("el.log.{}.gz", index));
+        let file = File::open(path).ok()?;
+        let mut out = String::new();
+        GzDecoder::new(file).read_to_string(&mut out).unwrap();
+        Some(out)
+    }
+
+    fn archives(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|n| n != "el.log")
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn keep_zero_truncates_without_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = open(dir.path(), 0);
+
+        log.write_line("one").unwrap();
+        log.write_line("two").unwrap();
+
+        assert!(archives(dir.path()).is_empty());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("el.log")).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn keep_one_holds_only_the_latest_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = open(dir.path(), 1);
+
+        log.write_line("one").unwrap();
+        log.write_line("two").unwrap();
+
+        assert_eq!(archives(dir.path()), ["el.log.1.gz"]);
+        assert!(archive(dir.path(), 1).unwrap().ends_with(" two\n"));
+    }
+
+    #[test]
+    fn keep_n_shifts_archives_and_drops_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = open(dir.path(), 3);
+
+        for line in ["one", "two", "three", "four"] {
+            log.write_line(line).unwrap();
+        }
+
+        assert_eq!(
+            archives(dir.path()),
+            ["el.log.1.gz", "el.log.2.gz", "el.log.3.gz"]
+        );
+        assert!(archive(dir.path(), 1).unwrap().ends_with(" four\n"));
+        assert!(archive(dir.path(), 2).unwrap().ends_with(" three\n"));
+        assert!(archive(dir.path(), 3).unwrap().ends_with(" two\n"));
+    }
+
+    #[test]
+    fn rotates_once_the_size_limit_is_reached() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = open(dir.path(), 2);
+        log.max_bytes = 100;
+
+        log.write_line("short").unwrap();
+        assert!(archives(dir.path()).is_empty());
+
+        log.write_line(&"x".repeat(100)).unwrap();
+        assert_eq!(archives(dir.path()), ["el.log.1.gz"]);
+        assert_eq!(log.written, 0);
+    }
+
+    #[tokio::test]
+    async fn next_line_survives_invalid_utf8() {
+        let mut reader: &[u8] = b"one\n\xff\xfe two\r\nthree";
+        let mut buf = Vec::new();
+        let mut lines = Vec::new();
+        while let Some(line) = next_line(&mut reader, &mut buf).await {
+            lines.push(line);
+        }
+        assert_eq!(lines, ["one", "\u{fffd}\u{fffd} two", "three"]);
+    }
+}
@@ -1,51 +1,250 @@
 mod chains;
 mod cli;
+mod clients;
 mod config;
+mod daemon;
+mod download;
 mod install;
 mod layout;
+mod logs;
+mod manifest;
+mod metrics;
 mod runner;
+mod settings;
 mod status;
+mod tui;
+mod upgrade;
+mod verify;
+mod versions;
+
+use std::time::{Duration, SystemTime};
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, LogClient, NodeArgs, StatusFormat};
 
-use crate::chains::mainnet_config;
-use crate::install::{download_lighthouse, download_reth, ensure_jwt};
-use crate::layout::{bin_dir, log_dir};
-use crate::runner::{spawn_cl, spawn_el, start_nodes};
+use crate::config::{ClConfig, ElConfig};
+use crate::install::{ensure_clients, ensure_jwt, install, install_from};
+use crate::layout::log_dir;
+use crate::logs::reader::{Query, show};
+use crate::runner::{CL_COLOR, EL_COLOR, RunOptions, start_nodes};
+use crate::settings::Settings;
 use crate::status::logic::*;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
+    let settings = Settings::load()?;
+    // both clients may use their full grace period, plus some slack
+    let stop_timeout = settings.shutdown.grace_period() * 2 + Duration::from_secs(10);
 
     match args.command {
-        Commands::Run { quiet } => {
-            let bin_dir = bin_dir();
-            if !bin_dir.join("reth").exists() {
-                download_reth().await?;
+        Commands::Run {
+            quiet,
+            tui,
+            metrics_addr,
+            grace_period,
+            detached,
+            node,
+        } => {
+            let (el_cfg, cl_cfg) = settings.node_config(&node)?;
+            if let Some(state) = daemon::running(&el_cfg.chain)? {
+                anyhow::bail!(
+                    "ethup is already running on {} (pid {}); stop it with `eth stop --chain {}`",
+                    el_cfg.chain,
+                    state.supervisor_pid,
+                    el_cfg.chain
+                );
             }
 
-            if !bin_dir.join("lighthouse").exists() {
-                download_lighthouse().await?;
+            clients::el::preflight(&el_cfg)?;
+            if !el_cfg.embedded_cl {
+                clients::cl::preflight(&cl_cfg)?;
             }
-
+            ensure_clients(&required_clients(&el_cfg, &cl_cfg), &settings).await?;
             let _ = ensure_jwt().await?;
-            let (el_hoodi, cl_hoodi) = mainnet_config();
-            let mut el = spawn_el(&el_hoodi, quiet)?;
-            let mut cl = spawn_cl(&cl_hoodi, quiet)?;
-
             if quiet {
-                println!("Running quietly. Logs at {}", log_dir().display());
+                println!(
+                    "Running quietly. Logs at {}",
+                    log_dir(&el_cfg.chain).display()
+                );
+            }
+
+            let opts = RunOptions {
+                quiet,
+                tui,
+                detached,
+                metrics_addr: settings.metrics_addr(metrics_addr)?,
+                logs: &settings.logs,
+                grace: grace_period.unwrap_or(settings.shutdown.grace_period()),
+                restart: &settings.restart,
+            };
+            let res = start_nodes(&el_cfg, &cl_cfg, &opts).await;
+            daemon::clear_state(&el_cfg.chain);
+            res?;
+        }
+        Commands::Start { node, .. } => {
+            let (el_cfg, cl_cfg) = settings.node_config(&node)?;
+            clients::el::preflight(&el_cfg)?;
+            if !el_cfg.embedded_cl {
+                clients::cl::preflight(&cl_cfg)?;
             }
+            ensure_clients(&required_clients(&el_cfg, &cl_cfg), &settings).await?;
 
-            start_nodes(&mut el, &mut cl, quiet).await?;
+            // the chain is pinned so `eth restart` brings back the same node
+            // whatever the environment it runs in
+            let mut args: Vec<String> = std::env::args().skip(2).collect();
+            if node.chain.is_none() {
+                args.extend(["--chain".to_string(), el_cfg.chain.clone()]);
+            }
+            daemon::start(&el_cfg.chain, &args).await?;
+        }
+        Commands::Stop { chain } => {
+            let chain = node_chain(chain.as_deref(), &settings)?;
+            if daemon::stop(&chain, stop_timeout).await?.is_none() {
+                println!("ethup is not running on {}", chain);
+            }
+        }
+        Commands::Restart { chain } => {
+            let chain = node_chain(chain.as_deref(), &settings)?;
+            let Some(state) = daemon::running(&chain)? else {
+                anyhow::bail!("ethup is not running on {}; use `eth start`", chain);
+            };
+            daemon::restart(&state, stop_timeout).await?;
+        }
+        Commands::Status {
+            node,
+            format,
+            timeout,
+            watch,
+        } => {
+            let running = daemon::running(&node_chain(node.chain.as_deref(), &settings)?)?;
+            let node = match running {
+                Some(ref state) => daemon_node_args(node, state)?,
+                None => node,
+            };
+            let (el, cl) = settings.node_config(&node)?;
+            let timeout = timeout.unwrap_or(settings.status.timeout());
+            match watch {
+                Some(_) if !matches!(format, StatusFormat::Table) => {
+                    anyhow::bail!("--watch only works with --format table");
+                }
+                Some(interval) => status::watch::watch(&el, &cl, timeout, interval).await?,
+                None => status(&el, &cl, format, timeout, running).await?,
+            }
+        }
+        Commands::Install { clients, from } => {
+            if from.is_some() && clients.len() > 1 {
+                anyhow::bail!("--from installs a single client");
+            }
+            install::migrate_legacy_installs().await?;
+
+            for spec in clients {
+                let (client, version) = match spec.split_once('@') {
+                    Some((client, version)) => (client, Some(version)),
+                    None => (spec.as_str(), None),
+                };
+                match from {
+                    Some(ref archive) => install_from(client, version, archive, &settings).await?,
+                    None => install(client, version, &settings).await?,
+                };
+            }
+        }
+        Commands::List => {
+            install::migrate_legacy_installs().await?;
+            versions::list()?;
+        }
+        Commands::Use { client, version } => {
+            install::migrate_legacy_installs().await?;
+            versions::use_version(&client, &version, &settings)?;
+        }
+        Commands::Uninstall { clients } => {
+            install::migrate_legacy_installs().await?;
+            for spec in clients {
+                versions::uninstall(&spec)?;
+            }
+        }
+        Commands::Upgrade { client } => {
+            upgrade::upgrade(&client, &settings, stop_timeout).await?;
         }
-        Commands::Status => {
-            let (el, cl) = mainnet_config();
-            status(&el, &cl).await?;
+        Commands::Logs {
+            client,
+            chain,
+            follow,
+            lines,
+            grep,
+            since,
+        } => {
+            let clients: &[(&str, &str)] = match client {
+                LogClient::El => &[("EL", EL_COLOR)],
+                LogClient::Cl => &[("CL", CL_COLOR)],
+                LogClient::All => &[("EL", EL_COLOR), ("CL", CL_COLOR)],
+            };
+            let query = Query {
+                lines: lines.or(since.is_none().then_some(50)),
+                grep: grep.as_deref().map(regex::Regex::new).transpose()?,
+                since: since.map(|d| SystemTime::now() - d),
+            };
+            let chain = node_chain(chain.as_deref(), &settings)?;
+            show(&chain, clients, query, follow, settings.logs.keep).await?;
         }
     }
 
     Ok(())
 }
+
+// the chain of the node a command acts on: the flag, else the only running
+// node, else the configured chain
+fn node_chain(flag: Option<&str>, settings: &Settings) -> anyhow::Result<String> {
+    if flag.is_some() {
+        return Ok(settings.chain(flag)?.to_string());
+    }
+
+    let running = daemon::running_all()?;
+    match running.as_slice() {
+        [] => Ok(settings.chain(None)?.to_string()),
+        [state] => Ok(state.chain.clone()),
+        _ => {
+            let chains: Vec<&str> = running.iter().map(|s| s.chain.as_str()).collect();
+            anyhow::bail!(
+                "ethup is running on {}; pick one with --chain",
+                chains.join(", ")
+            )
+        }
+    }
+}
+
+// without flags picking the clients, `eth status` describes the running node
+fn daemon_node_args(mut node: NodeArgs, state: &daemon::State) -> anyhow::Result<NodeArgs> {
+    let explicit = node.el_client.is_some()
+        || node.cl_client.is_some()
+        || node.caplin
+        || node.el.http_port.is_some()
+        || node.cl.http_port.is_some();
+    if explicit {
+        return Ok(node);
+    }
+
+    node.chain = Some(state.chain.clone());
+    node.el_client = Some(state.el.client.parse().map_err(anyhow::Error::msg)?);
+    node.el.http_port = Some(state.el.http_port);
+    match state.cl {
+        Some(ref cl) => {
+            node.cl_client = Some(cl.client.parse().map_err(anyhow::Error::msg)?);
+            node.cl.http_port = Some(cl.http_port);
+        }
+        None => {
+            node.caplin = true;
+            node.cl.http_port = state.el.beacon_port;
+        }
+    }
+
+    Ok(node)
+}
+
+fn required_clients<'a>(el: &'a ElConfig, cl: &'a ClConfig) -> Vec<&'a str> {
+    if el.embedded_cl {
+        vec![el.client.name()]
+    } else {
+        vec![el.client.name(), cl.client.name()]
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::layout::manifest_file;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Manifest {
+    #[serde(default)]
+    pub clients: BTreeMap<String, InstalledClient>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InstalledClient {
+    pub version: String,
+    pub installed_at: u64,
+}
+
+impl Manifest {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = manifest_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let raw = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("invalid manifest {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = manifest_file();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn version(&self, client: &str) -> Option<&str> {
+        self.clients.get(client).map(|c| c.version.as_str())
+    }
+
+    pub fn record(client: &str, version: &str) -> anyhow::Result<()> {
+        let mut manifest = Self::load()?;
+        let installed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+
+        manifest.clients.insert(
+            client.to_string(),
+            InstalledClient {
+                version: version.to_string(),
+                installed_at,
+            },
+        );
+        manifest.save()
+    }
+}
@@ -0,0 +1,380 @@
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::config::{ClConfig, ElConfig};
+use crate::runner::Role;
+use crate::status::logic::spawn_poller;
+use crate::status::types::{ClHealth, ElSyncState, StatusReport};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const POLL_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST: usize = 8 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct Process {
+    client: String,
+    up: bool,
+    spawns: u64,
+    started_at: Option<Instant>,
+}
+
+struct Inner {
+    started_at: Instant,
+    embedded_cl: bool,
+    el: Process,
+    cl: Process,
+    status: Option<StatusReport>,
+}
+
+// shared between the supervisor, which reports process changes, and the
+// /metrics server
+#[derive(Clone)]
+pub struct Metrics(Arc<Mutex<Inner>>);
+
+impl Metrics {
+    fn new(el: &ElConfig, cl: &ClConfig) -> Self {
+        Self(Arc::new(Mutex::new(Inner {
+            started_at: Instant::now(),
+            embedded_cl: el.embedded_cl,
+            el: Process {
+                client: el.client.to_string(),
+                ..Default::default()
+            },
+            cl: Process {
+                client: cl.client.to_string(),
+                ..Default::default()
+            },
+            status: None,
+        })))
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut Inner) -> T) -> T {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut inner)
+    }
+
+    pub fn started(&self, role: Role) {
+        self.with(|inner| {
+            let p = inner.process(role);
+            p.up = true;
+            p.spawns += 1;
+            p.started_at = Some(Instant::now());
+        });
+    }
+
+    pub fn stopped(&self, role: Role) {
+        self.with(|inner| {
+            let p = inner.process(role);
+            p.up = false;
+            p.started_at = None;
+        });
+    }
+
+    fn render(&self) -> String {
+        self.with(|inner| inner.render())
+    }
+}
+
+impl Inner {
+    fn process(&mut self, role: Role) -> &mut Process {
+        match role {
+            Role::El => &mut self.el,
+            Role::Cl => &mut self.cl,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = Out::default();
+
+        out.metric(
+            "ethup_uptime_seconds",
+            "gauge",
+            "Seconds since the supervisor started",
+        );
+        out.sample(
+            "ethup_uptime_seconds",
+            "",
+            self.started_at.elapsed().as_secs(),
+        );
+
+        let mut processes = vec![("el", &self.el)];
+        if !self.embedded_cl {
+            processes.push(("cl", &self.cl));
+        }
+
+        out.metric(
+            "ethup_process_up",
+            "gauge",
+            "Whether the client process is running",
+        );
+        for (role, p) in &processes {
+            out.sample("ethup_process_up", &labels(role, &p.client), p.up as u64);
+        }
+
+        out.metric(
+            "ethup_process_restarts_total",
+            "counter",
+            "Times the client process was restarted by the supervisor",
+        );
+        for (role, p) in &processes {
+            out.sample(
+                "ethup_process_restarts_total",
+                &labels(role, &p.client),
+                p.spawns.saturating_sub(1),
+            );
+        }
+
+        out.metric(
+            "ethup_process_uptime_seconds",
+            "gauge",
+            "Seconds since the client process was last started",
+        );
+        for (role, p) in &processes {
+            let uptime = p.started_at.map_or(0, |t| t.elapsed().as_secs());
+            out.sample(
+                "ethup_process_uptime_seconds",
+                &labels(role, &p.client),
+                uptime,
+            );
+        }
+
+        if let Some(ref report) = self.status {
+            render_status(&mut out, report);
+        }
+
+        out.0
+    }
+}
+
+fn render_status(out: &mut Out, report: &StatusReport) {
+    let el = &report.execution;
+    let cl = &report.consensus;
+    let el_labels = labels("el", &el.client);
+    let cl_labels = labels("cl", &cl.client);
+
+    out.metric(
+        "ethup_client_reachable",
+        "gauge",
+        "Whether the client answered its last status poll",
+    );
+    out.sample("ethup_client_reachable", &el_labels, el.reachable as u64);
+    out.sample("ethup_client_reachable", &cl_labels, cl.reachable as u64);
+
+    if let Some(ref s) = el.status {
+        out.gauge(
+            "ethup_el_head_block",
+            "Latest executed block",
+            &el_labels,
+            s.head_block,
+        );
+        let percent = match s.sync {
+            ElSyncState::FullySynced => 100.0,
+            ElSyncState::Syncing { percent, .. } => percent,
+        };
+        out.gauge(
+            "ethup_el_sync_percent",
+            "Execution sync progress",
+            &el_labels,
+            percent,
+        );
+        out.gauge(
+            "ethup_el_peers",
+            "Execution layer peers",
+            &el_labels,
+            s.peers,
+        );
+        out.gauge(
+            "ethup_el_chain_id",
+            "Chain ID reported by the EL",
+            &el_labels,
+            s.chain_id,
+        );
+    }
+
+    if let Some(ref s) = cl.status {
+        out.gauge(
+            "ethup_cl_head_slot",
+            "Head slot of the beacon node",
+            &cl_labels,
+            s.head_slot,
+        );
+        if let Some(epoch) = s.finalized_epoch {
+            out.gauge(
+                "ethup_cl_finalized_epoch",
+                "Latest finalized epoch",
+                &cl_labels,
+                epoch,
+            );
+        }
+        if let Some(distance) = s.sync_distance {
+            out.gauge(
+                "ethup_cl_sync_distance",
+                "Slots behind the head of the chain",
+                &cl_labels,
+                distance,
+            );
+        }
+        out.gauge(
+            "ethup_cl_syncing",
+            "Whether the beacon node is syncing",
+            &cl_labels,
+            s.is_syncing as u64,
+        );
+        out.gauge(
+            "ethup_cl_peers",
+            "Consensus layer peers",
+            &cl_labels,
+            s.peers,
+        );
+
+        out.metric(
+            "ethup_cl_health",
+            "gauge",
+            "Beacon node health endpoint; 1 for the current status",
+        );
+        let current = match s.health {
+            ClHealth::Healthy => "healthy",
+            ClHealth::Syncing => "syncing",
+            ClHealth::Unhealthy => "unhealthy",
+            ClHealth::Unknown(_) => "unknown",
+        };
+        for status in ["healthy", "syncing", "unhealthy", "unknown"] {
+            out.sample(
+                "ethup_cl_health",
+                &format!("{},status=\"{}\"", cl_labels, status),
+                (status == current) as u64,
+            );
+        }
+    }
+}
+
+fn labels(role: &str, client: &str) -> String {
+    format!("role=\"{}\",client=\"{}\"", role, client)
+}
+
+// prometheus text exposition format
+#[derive(Default)]
+struct Out(String);
+
+impl Out {
+    fn metric(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {} {}", name, help);
+        let _ = writeln!(self.0, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &str, value: impl std::fmt::Display) {
+        if labels.is_empty() {
+            let _ = writeln!(self.0, "{} {}", name, value);
+        } else {
+            let _ = writeln!(self.0, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, labels: &str, value: impl std::fmt::Display) {
+        self.metric(name, "gauge", help);
+        self.sample(name, labels, value);
+    }
+}
+
+// binds before the clients are spawned so a taken port fails the run early
+pub async fn serve(addr: SocketAddr, el: &ElConfig, cl: &ClConfig) -> anyhow::Result<Metrics> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to bind metrics address {}: {}", addr, e))?;
+
+    let metrics = Metrics::new(el, cl);
+
+    let poller = metrics.clone();
+    spawn_poller(el, cl, POLL_INTERVAL, POLL_TIMEOUT, move |report| {
+        poller.with(|inner| inner.status = Some(report));
+        true
+    });
+
+    let server = metrics.clone();
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    // errors like EMFILE last a while, so don't spin on them
+                    eprintln!("metrics: failed to accept connection: {}", e);
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let metrics = server.clone();
+            tokio::spawn(async move {
+                let _ = handle(stream, &metrics).await;
+            });
+        }
+    });
+
+    Ok(metrics)
+}
+
+async fn handle(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    // only the request line matters, but read up to the end of the headers
+    // so the client sees a clean response
+    let read = tokio::time::timeout(READ_TIMEOUT, async {
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Ok::<_, std::io::Error>(())
+    })
+    .await;
+    if !matches!(read, Ok(Ok(()))) {
+        return Ok(());
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut parts = request.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            metrics.render(),
+        ),
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "ethup metrics at /metrics\n".to_string(),
+        ),
+        ("GET", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "method not allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -1,130 +1,633 @@
-use std::process::Stdio;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
 
 use tokio::{
-    io::{AsyncBufReadExt, BufReader},
-    process::{Child, ChildStdout, Command},
-    signal,
+    io::{AsyncRead, BufReader},
+    process::Child,
+    signal::unix::{SignalKind, signal},
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    time::{Instant, sleep_until},
 };
 
+use crate::clients::{cl, el};
 use crate::config::{ClConfig, ElConfig};
+use crate::daemon::{self, send_signal};
+use crate::logs::writer::{forward_lines, next_line, spawn_writer};
+use crate::metrics::{self, Metrics};
+use crate::settings::{LogSettings, RestartSettings};
+use crate::tui::{self, Control, Tui};
 
-pub fn spawn_el(cfg: &ElConfig, quiet: bool) -> anyhow::Result<Child> {
+pub const EL_COLOR: &str = "\x1b[32m";
+pub const CL_COLOR: &str = "\x1b[34m";
+pub const STDERR_COLOR: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+pub fn spawn_el(cfg: &ElConfig, cl: &ClConfig, debug: bool) -> anyhow::Result<Child> {
     std::fs::create_dir_all(&cfg.data_dir)?;
 
-    let mut cmd = Command::new(&cfg.bin);
-    cmd.arg("node")
-        .arg("--chain")
-        .arg(&cfg.chain)
-        .arg("--datadir")
-        .arg(&cfg.data_dir)
-        .arg("--authrpc.addr")
-        .arg(&cfg.authrpc_addr)
-        .arg("--authrpc.port")
-        .arg(cfg.authrpc_port.to_string())
-        .arg("--authrpc.jwtsecret")
-        .arg(&cfg.jwt_path)
-        .arg("--http")
-        .arg("--http.addr")
-        .arg(&cfg.http_addr)
-        .arg("--http.port")
-        .arg(cfg.http_port.to_string())
-        .arg("--http.api")
-        .arg("all");
-	
-    if quiet {
-        cmd.stdout(Stdio::null()).stderr(Stdio::null());
-    } else {
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut cmd = el::command(cfg, cl);
+    if debug {
+        el::debug_logging(&mut cmd, cfg.client);
     }
+    cmd.args(&cfg.extra_args);
+
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0);
 
     Ok(cmd.spawn()?)
 }
 
-pub fn spawn_cl(cfg: &ClConfig, quiet: bool) -> anyhow::Result<Child> {
+pub fn spawn_cl(cfg: &ClConfig, debug: bool) -> anyhow::Result<Child> {
     std::fs::create_dir_all(&cfg.data_dir)?;
 
-    let mut cmd = Command::new(&cfg.bin);
-    cmd.arg("bn")
-        .arg("--network")
-        .arg(&cfg.chain)
-        .arg("--listen-address")
-        .arg("0.0.0.0")
-        .arg("--datadir")
-        .arg(&cfg.data_dir)
-        .arg("--execution-endpoint")
-        .arg(&cfg.execution_endpoint)
-        .arg("--execution-jwt")
-        .arg(&cfg.execution_jwt)
-        .arg("--http")
-        .arg("--http-address")
-        .arg(&cfg.http_addr)
-        .arg("--http-port")
-        .arg(cfg.http_port.to_string());
-
-    if let Some(ref url) = cfg.checkpoint_sync_url {
-        cmd.arg("--checkpoint-sync-url").arg(url);
-    }
-
-    if quiet {
-        cmd.stdout(Stdio::null()).stderr(Stdio::null());
-    } else {
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut cmd = cl::command(cfg);
+    if debug {
+        cl::debug_logging(&mut cmd, cfg.client);
     }
+    cmd.args(&cfg.extra_args);
+
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0);
 
     Ok(cmd.spawn()?)
 }
 
-pub async fn start_nodes(el: &mut Child, cl: &mut Child, quiet: bool) -> anyhow::Result<()> {
-    if !quiet {
-        if let Some(stdout) = el.stdout.take() {
-            tokio::spawn(stream_logs("EL", "\x1b[32m", stdout));
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    El,
+    Cl,
+}
+
+impl Role {
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::El => "EL",
+            Role::Cl => "CL",
         }
-        if let Some(stdout) = cl.stdout.take() {
-            tokio::spawn(stream_logs("CL", "\x1b[34m", stdout));
+    }
+}
+
+pub struct RunOptions<'a> {
+    pub quiet: bool,
+    pub tui: bool,
+    // started in the background by `eth start`
+    pub detached: bool,
+    pub metrics_addr: Option<SocketAddr>,
+    pub logs: &'a LogSettings,
+    pub grace: Duration,
+    pub restart: &'a RestartSettings,
+}
+
+// supervisor messages go to stderr, or to the TUI footer while it owns the
+// terminal
+#[derive(Clone)]
+pub struct Notifier(Option<UnboundedSender<tui::Event>>);
+
+impl Notifier {
+    pub fn say(&self, msg: String) {
+        match self.0 {
+            Some(ref tx) => {
+                let _ = tx.send(tui::Event::Notice(msg));
+            }
+            None => eprintln!("{}", msg),
         }
     }
+}
+
+enum Output {
+    Terminal,
+    Files(UnboundedSender<String>),
+    Tui(UnboundedSender<tui::Event>),
+}
+
+struct Managed {
+    role: &'static str,
+    err_prefix: &'static str,
+    color: &'static str,
+    pane: Role,
+    child: Option<Child>,
+    restart_at: Option<Instant>,
+    restarts: VecDeque<Instant>,
+    output: Output,
+    notify: Notifier,
+    metrics: Option<Metrics>,
+    // started with debug logging, see Control::ToggleDebug
+    debug: bool,
+}
+
+impl Managed {
+    fn new(
+        pane: Role,
+        chain: &str,
+        opts: &RunOptions,
+        tui: Option<&Tui>,
+        notify: &Notifier,
+        metrics: Option<&Metrics>,
+    ) -> anyhow::Result<Self> {
+        let (role, err_prefix, color) = match pane {
+            Role::El => ("EL", "EL!", EL_COLOR),
+            Role::Cl => ("CL", "CL!", CL_COLOR),
+        };
+
+        let output = match tui {
+            Some(tui) => Output::Tui(tui.events.clone()),
+            None if opts.quiet => Output::Files(spawn_writer(chain, role, opts.logs)?),
+            None => Output::Terminal,
+        };
+
+        Ok(Self {
+            role,
+            err_prefix,
+            color,
+            pane,
+            child: None,
+            restart_at: None,
+            restarts: VecDeque::new(),
+            output,
+            notify: notify.clone(),
+            metrics: metrics.cloned(),
+            debug: false,
+        })
+    }
 
-    tokio::select! {
-        _ = signal::ctrl_c()  => {
-            eprintln!("Ctrl+C recieved, shutting down clients...");
+    fn attach(&mut self, mut child: Child) {
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
 
-            if let Some(id) = el.id() {
-                eprintln!("Killing EL pid {}", id);
-                let _ = el.kill().await;
+        match self.output {
+            Output::Files(ref tx) => {
+                if let Some(stdout) = stdout {
+                    tokio::spawn(forward_lines(stdout, tx.clone()));
+                }
+                if let Some(stderr) = stderr {
+                    tokio::spawn(forward_lines(stderr, tx.clone()));
+                }
             }
+            Output::Terminal => {
+                if let Some(stdout) = stdout {
+                    tokio::spawn(stream_logs(self.role, self.color, stdout));
+                }
+                if let Some(stderr) = stderr {
+                    tokio::spawn(stream_logs(self.err_prefix, STDERR_COLOR, stderr));
+                }
+            }
+            Output::Tui(ref tx) => {
+                if let Some(stdout) = stdout {
+                    tokio::spawn(send_lines(self.pane, false, stdout, tx.clone()));
+                }
+                if let Some(stderr) = stderr {
+                    tokio::spawn(send_lines(self.pane, true, stderr, tx.clone()));
+                }
+            }
+        }
+
+        self.child = Some(child);
+        self.restart_at = None;
+        if let Some(ref metrics) = self.metrics {
+            metrics.started(self.pane);
+        }
+    }
+
+    fn pid(&self) -> Option<u32> {
+        self.child.as_ref().and_then(|c| c.id())
+    }
+
+    fn exited(&mut self, status: ExitStatus, policy: &RestartSettings) -> anyhow::Result<()> {
+        self.child = None;
+        if let Some(ref metrics) = self.metrics {
+            metrics.stopped(self.pane);
+        }
 
-            if let Some(id) = cl.id() {
-                eprintln!("Killing CL pid {}", id);
-                let _ = cl.kill().await;
+        let now = Instant::now();
+        let restart_at = match next_restart(&mut self.restarts, now, policy) {
+            Restart::Disabled => {
+                self.notify
+                    .say(format!("{} exited with status {}", self.role, status));
+                anyhow::bail!("{} exited unexpectedly", self.role);
+            }
+            Restart::GiveUp => {
+                self.notify
+                    .say(format!("{} exited with status {}", self.role, status));
+                anyhow::bail!(
+                    "{} crashed {} times within {}s, giving up",
+                    self.role,
+                    self.restarts.len() + 1,
+                    policy.window_secs
+                );
             }
-        },
+            Restart::At(at) => at,
+        };
+        self.restart_at = Some(restart_at);
+        let backoff = restart_at - now;
+
+        self.notify.say(format!(
+            "{} exited with status {}, restarting in {}s (restart {}/{} within {}s)",
+            self.role,
+            status,
+            backoff.as_secs(),
+            self.restarts.len(),
+            policy.max_restarts,
+            policy.window_secs
+        ));
+
+        Ok(())
+    }
+
+    async fn stop(&mut self, grace: Duration) {
+        if let Some(ref mut child) = self.child {
+            stop_child(self.role, child, grace, &self.notify).await;
+        }
+        self.child = None;
+        if let Some(ref metrics) = self.metrics {
+            metrics.stopped(self.pane);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Restart {
+    Disabled,
+    GiveUp,
+    At(Instant),
+}
 
-        status = el.wait() => {
-            let status = status?;
-            eprintln!("EL exited with status {}", status);
+// decides what happens after a crash at `now`; `restarts` holds the times of
+// earlier restarts and gets this one added when it goes ahead
+fn next_restart(
+    restarts: &mut VecDeque<Instant>,
+    now: Instant,
+    policy: &RestartSettings,
+) -> Restart {
+    if !policy.enabled {
+        return Restart::Disabled;
+    }
+
+    while restarts
+        .front()
+        .is_some_and(|&t| now.duration_since(t) > policy.window())
+    {
+        restarts.pop_front();
+    }
+    if restarts.len() >= policy.max_restarts {
+        return Restart::GiveUp;
+    }
+
+    let backoff = policy.backoff(restarts.len());
+    restarts.push_back(now);
+    Restart::At(now + backoff)
+}
+
+pub async fn start_nodes(
+    el_cfg: &ElConfig,
+    cl_cfg: &ClConfig,
+    opts: &RunOptions<'_>,
+) -> anyhow::Result<()> {
+    let metrics = match opts.metrics_addr {
+        Some(addr) => Some(metrics::serve(addr, el_cfg, cl_cfg).await?),
+        None => None,
+    };
 
-            let _ = cl.kill().await;
-            return Err(anyhow::anyhow!("EL exited unexpectedly"));
-        },
+    let (tui, mut control) = match opts.tui {
+        true => {
+            let (tui, control) = Tui::start(el_cfg, cl_cfg)?;
+            (Some(tui), Some(control))
+        }
+        false => (None, None),
+    };
+    let notify = Notifier(tui.as_ref().map(|t| t.events.clone()));
+
+    let chain = &el_cfg.chain;
+    let mut el = Managed::new(
+        Role::El,
+        chain,
+        opts,
+        tui.as_ref(),
+        &notify,
+        metrics.as_ref(),
+    )?;
+    let mut cl = Managed::new(
+        Role::Cl,
+        chain,
+        opts,
+        tui.as_ref(),
+        &notify,
+        metrics.as_ref(),
+    )?;
 
-        status = cl.wait() => {
-            let status = status?;
-            eprintln!("CL exited with status {}", status);
+    let res = supervise(el_cfg, &mut el, cl_cfg, &mut cl, opts, &mut control).await;
 
-            let _ = el.kill().await;
-            return Err(anyhow::anyhow!("CL exited unexpectedly"));
-        },
+    // the CL depends on the EL's engine API, so it goes down first
+    cl.stop(opts.grace).await;
+    el.stop(opts.grace).await;
+
+    if let Some(tui) = tui {
+        tui.close().await?;
     }
 
-    Ok(())
+    res
 }
 
-async fn stream_logs(prefix: &str, color: &str, stdout: ChildStdout) {
-    let mut reader = BufReader::new(stdout).lines();
+async fn supervise(
+    el_cfg: &ElConfig,
+    el: &mut Managed,
+    cl_cfg: &ClConfig,
+    cl: &mut Managed,
+    opts: &RunOptions<'_>,
+    control: &mut Option<UnboundedReceiver<Control>>,
+) -> anyhow::Result<()> {
+    let notify = el.notify.clone();
+
+    // the clients run in their own process groups, so the handlers have to
+    // be in place before they are spawned or a signal would orphan them
+    let mut sigint = signal(SignalKind::interrupt())?;
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+    // sent by `eth restart` and `eth upgrade` to a foreground supervisor
+    let mut sigusr1 = signal(SignalKind::user_defined1())?;
+
+    el.attach(spawn_el(el_cfg, cl_cfg, el.debug)?);
+    if !el_cfg.embedded_cl {
+        cl.attach(spawn_cl(cl_cfg, cl.debug)?);
+    }
+    record_state(el_cfg, el, cl_cfg, cl, opts);
+
+    loop {
+        tokio::select! {
+            _ = sigint.recv() => {
+                notify.say("Ctrl+C received, shutting down clients...".to_string());
+                return Ok(());
+            },
 
-    const RESET: &str = "\x1b[0m";
+            _ = sigterm.recv() => {
+                notify.say("SIGTERM received, shutting down clients...".to_string());
+                return Ok(());
+            },
+
+            _ = sighup.recv() => {
+                notify.say("SIGHUP received, shutting down clients...".to_string());
+                return Ok(());
+            },
+
+            _ = sigusr1.recv() => {
+                notify.say("SIGUSR1 received, restarting clients...".to_string());
+                cl.stop(opts.grace).await;
+                el.stop(opts.grace).await;
+                el.attach(spawn_el(el_cfg, cl_cfg, el.debug)?);
+                if !el_cfg.embedded_cl {
+                    cl.attach(spawn_cl(cl_cfg, cl.debug)?);
+                }
+                record_state(el_cfg, el, cl_cfg, cl, opts);
+            },
+
+            Some(ctl) = recv_opt(control) => match ctl {
+                Control::Quit => {
+                    notify.say("Shutting down clients...".to_string());
+                    return Ok(());
+                }
+                // a requested restart is not a crash, so it skips the backoff
+                // and does not count against the restart budget
+                Control::Restart(Role::El) => {
+                    notify.say("Restarting EL".to_string());
+                    el.stop(opts.grace).await;
+                    el.attach(spawn_el(el_cfg, cl_cfg, el.debug)?);
+                    record_state(el_cfg, el, cl_cfg, cl, opts);
+                }
+                Control::Restart(Role::Cl) if el_cfg.embedded_cl => {
+                    notify.say(format!(
+                        "The CL is embedded in {}, restart the EL instead",
+                        el_cfg.client
+                    ));
+                }
+                Control::Restart(Role::Cl) => {
+                    notify.say("Restarting CL".to_string());
+                    cl.stop(opts.grace).await;
+                    cl.attach(spawn_cl(cl_cfg, cl.debug)?);
+                    record_state(el_cfg, el, cl_cfg, cl, opts);
+                }
+                Control::ToggleDebug(Role::El) => {
+                    el.debug = !el.debug;
+                    notify.say(format!("Restarting EL with {} logging", log_level(el.debug)));
+                    el.stop(opts.grace).await;
+                    el.attach(spawn_el(el_cfg, cl_cfg, el.debug)?);
+                    record_state(el_cfg, el, cl_cfg, cl, opts);
+                }
+                Control::ToggleDebug(Role::Cl) if el_cfg.embedded_cl => {
+                    notify.say(format!(
+                        "The CL is embedded in {}, change the EL's log level instead",
+                        el_cfg.client
+                    ));
+                }
+                Control::ToggleDebug(Role::Cl) => {
+                    cl.debug = !cl.debug;
+                    notify.say(format!("Restarting CL with {} logging", log_level(cl.debug)));
+                    cl.stop(opts.grace).await;
+                    cl.attach(spawn_cl(cl_cfg, cl.debug)?);
+                    record_state(el_cfg, el, cl_cfg, cl, opts);
+                }
+            },
+
+            status = wait(&mut el.child) => {
+                el.exited(status?, opts.restart)?;
+                record_state(el_cfg, el, cl_cfg, cl, opts);
+            },
+
+            status = wait(&mut cl.child) => {
+                cl.exited(status?, opts.restart)?;
+                record_state(el_cfg, el, cl_cfg, cl, opts);
+            },
+
+            _ = sleep_until_opt(el.restart_at) => {
+                notify.say("Restarting EL".to_string());
+                el.attach(spawn_el(el_cfg, cl_cfg, el.debug)?);
+                record_state(el_cfg, el, cl_cfg, cl, opts);
+            },
+
+            _ = sleep_until_opt(cl.restart_at) => {
+                notify.say("Restarting CL".to_string());
+                cl.attach(spawn_cl(cl_cfg, cl.debug)?);
+                record_state(el_cfg, el, cl_cfg, cl, opts);
+            },
+        }
+    }
+}
+
+fn log_level(debug: bool) -> &'static str {
+    if debug { "debug" } else { "default" }
+}
+
+fn record_state(
+    el_cfg: &ElConfig,
+    el: &Managed,
+    cl_cfg: &ClConfig,
+    cl: &Managed,
+    opts: &RunOptions<'_>,
+) {
+    let state = daemon::State::new(el_cfg, el.pid(), cl_cfg, cl.pid(), opts.detached);
+    if let Err(e) = daemon::write_state(&state) {
+        el.notify.say(format!("failed to write state file: {}", e));
+    }
+}
+
+async fn recv_opt(rx: &mut Option<UnboundedReceiver<Control>>) -> Option<Control> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn wait(child: &mut Option<Child>) -> std::io::Result<ExitStatus> {
+    match child {
+        Some(child) => child.wait().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn sleep_until_opt(at: Option<Instant>) {
+    match at {
+        Some(at) => sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
+
+pub async fn stop_child(role: &str, child: &mut Child, grace: Duration, notify: &Notifier) {
+    let Some(pid) = child.id() else {
+        return;
+    };
+
+    notify.say(format!("Stopping {} pid {}", role, pid));
+    send_signal(pid, libc::SIGTERM);
+
+    match tokio::time::timeout(grace, child.wait()).await {
+        Ok(Ok(status)) => notify.say(format!("{} exited with status {}", role, status)),
+        _ => {
+            notify.say(format!(
+                "{} did not exit within {}s, killing pid {}",
+                role,
+                grace.as_secs(),
+                pid
+            ));
+            let _ = child.kill().await;
+        }
+    }
+}
+
+async fn stream_logs<R>(prefix: &str, color: &str, output: R)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(output);
+    let mut buf = Vec::new();
+
+    while let Some(line) = next_line(&mut reader, &mut buf).await {
+        println!("{}", format_line(prefix, color, &line));
+    }
+}
+
+async fn send_lines<R>(pane: Role, stderr: bool, output: R, tx: UnboundedSender<tui::Event>)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(output);
+    let mut buf = Vec::new();
+
+    // the pipe is drained even after the TUI has closed
+    while let Some(line) = next_line(&mut reader, &mut buf).await {
+        let _ = tx.send(tui::Event::Line {
+            role: pane,
+            stderr,
+            text: line,
+        });
+    }
+}
+
+pub fn format_line(prefix: &str, color: &str, line: &str) -> String {
+    format!("{}[{}]{} {}", color, prefix, RESET, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartSettings {
+        RestartSettings {
+            max_restarts: 3,
+            window_secs: 60,
+            initial_backoff_secs: 1,
+            max_backoff_secs: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        let policy = RestartSettings {
+            max_restarts: 10,
+            ..policy()
+        };
+        let mut restarts = VecDeque::new();
+        let now = Instant::now();
+
+        let delays: Vec<u64> = (0..4)
+            .map(|_| match next_restart(&mut restarts, now, &policy) {
+                Restart::At(at) => (at - now).as_secs(),
+                other => panic!("expected a restart, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(delays, [1, 2, 3, 3]);
+        assert_eq!(policy.backoff(usize::MAX), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn gives_up_after_max_restarts_within_the_window() {
+        let mut restarts = VecDeque::new();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                next_restart(&mut restarts, now, &policy()),
+                Restart::At(_)
+            ));
+        }
+        assert_eq!(next_restart(&mut restarts, now, &policy()), Restart::GiveUp);
+        assert_eq!(restarts.len(), 3);
+    }
+
+    #[test]
+    fn restarts_outside_the_window_are_forgotten() {
+        let start = Instant::now();
+        let mut restarts = VecDeque::from([start, start, start]);
+
+        let later = start + Duration::from_secs(61);
+        assert_eq!(
+            next_restart(&mut restarts, later, &policy()),
+            Restart::At(later + Duration::from_secs(1))
+        );
+        assert_eq!(restarts, [later]);
+
+        // right at the edge of the window a restart still counts
+        let mut restarts = VecDeque::from([start, start, start]);
+        let edge = start + Duration::from_secs(60);
+        assert_eq!(
+            next_restart(&mut restarts, edge, &policy()),
+            Restart::GiveUp
+        );
+    }
 
-    while let Ok(Some(line)) = reader.next_line().await {
-        println!("{}[{}]{} {}", color, prefix, RESET, line);
+    #[test]
+    fn disabled_policy_never_restarts() {
+        let policy = RestartSettings {
+            enabled: false,
+            ..policy()
+        };
+        let mut restarts = VecDeque::new();
+        assert_eq!(
+            next_restart(&mut restarts, Instant::now(), &policy),
+            Restart::Disabled
+        );
+        assert!(restarts.is_empty());
     }
 }
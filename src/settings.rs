@@ -0,0 +1,408 @@
+use reqwest::Url;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::chains::{CHAINS, Chain, chain_config, find_chain};
+use crate::cli::NodeArgs;
+use crate::clients::{ClClient, ElClient};
+use crate::config::{ClConfig, ClOverrides, ElConfig, ElOverrides, Env, env_var, process_env};
+use crate::install::{client_names, installer};
+use crate::layout::config_file;
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub chain: Option<String>,
+    pub el: Option<ElClient>,
+    pub cl: Option<ClClient>,
+    pub caplin: Option<bool>,
+    pub mirror: Option<Url>,
+    pub metrics_addr: Option<SocketAddr>,
+    #[serde(default)]
+    pub chains: BTreeMap<String, ChainSettings>,
+    #[serde(default)]
+    pub logs: LogSettings,
+    #[serde(default)]
+    pub shutdown: ShutdownSettings,
+    #[serde(default)]
+    pub restart: RestartSettings,
+    #[serde(default)]
+    pub status: StatusSettings,
+    #[serde(default)]
+    pub versions: BTreeMap<String, String>,
+    #[serde(default)]
+    pub verify: VerifySettings,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSettings {
+    #[serde(default)]
+    pub el: ElOverrides,
+    #[serde(default)]
+    pub cl: ClOverrides,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LogSettings {
+    pub max_size_mb: u64,
+    pub keep: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            max_size_mb: 100,
+            keep: 5,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ShutdownSettings {
+    pub grace_period_secs: u64,
+}
+
+impl Default for ShutdownSettings {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: 60,
+        }
+    }
+}
+
+impl ShutdownSettings {
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct StatusSettings {
+    pub timeout_secs: u64,
+}
+
+impl Default for StatusSettings {
+    fn default() -> Self {
+        Self { timeout_secs: 5 }
+    }
+}
+
+impl StatusSettings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RestartSettings {
+    pub enabled: bool,
+    pub max_restarts: usize,
+    pub window_secs: u64,
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RestartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_restarts: 5,
+            window_secs: 600,
+            initial_backoff_secs: 1,
+            max_backoff_secs: 120,
+        }
+    }
+}
+
+impl RestartSettings {
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let secs = self
+            .initial_backoff_secs
+            .saturating_mul(1u64 << attempt.min(32))
+            .min(self.max_backoff_secs);
+        Duration::from_secs(secs)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct VerifySettings {
+    pub checksum: bool,
+    pub signature: bool,
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Default for VerifySettings {
+    fn default() -> Self {
+        Self {
+            checksum: true,
+            signature: false,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = config_file();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let raw = std::fs::read_to_string(&path)?;
+        Self::from_toml(&raw)
+            .map_err(|e| anyhow::anyhow!("invalid config file {}: {}", path.display(), e))
+    }
+
+    fn from_toml(raw: &str) -> anyhow::Result<Self> {
+        let settings: Settings = toml::from_str(raw)?;
+
+        for name in settings.chains.keys() {
+            if find_chain(name).is_err() {
+                let known: Vec<&str> = CHAINS.iter().map(|c| c.name).collect();
+                anyhow::bail!(
+                    "unknown key `chains.{}` (expected one of: {})",
+                    name,
+                    known.join(", ")
+                );
+            }
+        }
+
+        let client_keys = settings
+            .versions
+            .keys()
+            .map(|c| ("versions", c))
+            .chain(settings.verify.keys.keys().map(|c| ("verify.keys", c)));
+        for (table, client) in client_keys {
+            if installer(client).is_err() {
+                anyhow::bail!(
+                    "unknown key `{}.{}` (expected one of: {})",
+                    table,
+                    client,
+                    client_names().join(", ")
+                );
+            }
+        }
+
+        Ok(settings)
+    }
+
+    pub fn mirror(&self) -> anyhow::Result<Option<Url>> {
+        Ok(env_var(&process_env, "ETHUP_MIRROR")?.or_else(|| self.mirror.clone()))
+    }
+
+    pub fn metrics_addr(&self, flag: Option<SocketAddr>) -> anyhow::Result<Option<SocketAddr>> {
+        Ok(flag
+            .or(env_var(&process_env, "ETHUP_METRICS_ADDR")?)
+            .or(self.metrics_addr))
+    }
+
+    // the chain named by the flag, or the configured default
+    pub fn chain(&self, flag: Option<&str>) -> anyhow::Result<&'static str> {
+        Ok(self.pick_chain(flag, &process_env)?.name)
+    }
+
+    fn pick_chain(&self, flag: Option<&str>, env: Env) -> anyhow::Result<&'static Chain> {
+        let name = match flag {
+            Some(c) => c.to_string(),
+            None => env_var(env, "ETHUP_CHAIN")?
+                .or_else(|| self.chain.clone())
+                .unwrap_or_else(|| "mainnet".to_string()),
+        };
+        find_chain(&name)
+    }
+
+    pub fn node_config(&self, args: &NodeArgs) -> anyhow::Result<(ElConfig, ClConfig)> {
+        self.layer(args, &process_env)
+    }
+
+    // defaults < config file < environment < command line flags
+    fn layer(&self, args: &NodeArgs, env: Env) -> anyhow::Result<(ElConfig, ClConfig)> {
+        let chain = self.pick_chain(args.chain.as_deref(), env)?;
+        let el_client = match args.el_client {
+            Some(c) => c,
+            None => env_var(env, "ETHUP_EL")?
+                .or(self.el)
+                .unwrap_or(ElClient::Reth),
+        };
+        let cl_client = match args.cl_client {
+            Some(c) => c,
+            None => env_var(env, "ETHUP_CL")?
+                .or(self.cl)
+                .unwrap_or(ClClient::Lighthouse),
+        };
+        let (mut el, mut cl) = chain_config(chain, el_client, cl_client);
+
+        if let Some(file) = self.chains.get(chain.name) {
+            file.el.apply(&mut el);
+            file.cl.apply(&mut cl);
+        }
+
+        ElOverrides::from_env(env)?.apply(&mut el);
+        ClOverrides::from_env(env)?.apply(&mut cl);
+
+        args.el.apply(&mut el);
+        args.cl.apply(&mut cl);
+
+        el.embedded_cl = args.caplin
+            || env_var(env, "ETHUP_CAPLIN")?
+                .or(self.caplin)
+                .unwrap_or(false);
+        cl.execution_endpoint = el.authrpc_url();
+
+        Ok((el, cl))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+chain = "hoodi"
+el = "geth"
+
+[chains.hoodi.el]
+http_port = 1000
+authrpc_port = 1001
+extra_args = ["--from-file"]
+"#;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    fn error(raw: &str) -> String {
+        match Settings::from_toml(raw) {
+            Ok(_) => panic!("expected {:?} to be rejected", raw),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn defaults_without_file_env_or_flags() {
+        let (el, cl) = Settings::default()
+            .layer(&NodeArgs::default(), &env(&[]))
+            .unwrap();
+        assert_eq!(el.chain, "mainnet");
+        assert_eq!(el.client.name(), "reth");
+        assert_eq!(cl.client.name(), "lighthouse");
+        assert_eq!(el.http_port, 8545);
+        assert!(!el.embedded_cl);
+    }
+
+    #[test]
+    fn file_overrides_defaults() {
+        let settings = Settings::from_toml(FILE).unwrap();
+        let (el, _) = settings.layer(&NodeArgs::default(), &env(&[])).unwrap();
+        assert_eq!(el.chain, "hoodi");
+        assert_eq!(el.client.name(), "geth");
+        assert_eq!(el.http_port, 1000);
+        assert_eq!(el.authrpc_port, 1001);
+        assert_eq!(el.extra_args, ["--from-file"]);
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let settings = Settings::from_toml(FILE).unwrap();
+        let vars = [
+            ("ETHUP_EL", "besu"),
+            ("ETHUP_EL_HTTP_PORT", "2000"),
+            ("ETHUP_EL_EXTRA_ARGS", "--from-env"),
+        ];
+        let (el, _) = settings.layer(&NodeArgs::default(), &env(&vars)).unwrap();
+        assert_eq!(el.client.name(), "besu");
+        assert_eq!(el.http_port, 2000);
+        // untouched by the environment, so still from the file
+        assert_eq!(el.authrpc_port, 1001);
+        assert_eq!(el.extra_args, ["--from-file", "--from-env"]);
+    }
+
+    #[test]
+    fn flags_override_env() {
+        let settings = Settings::from_toml(FILE).unwrap();
+        let vars = [("ETHUP_CHAIN", "sepolia"), ("ETHUP_EL_HTTP_PORT", "2000")];
+        let mut args = NodeArgs {
+            chain: Some("hoodi".to_string()),
+            ..Default::default()
+        };
+        args.el.http_port = Some(3000);
+        args.el.extra_args = vec!["--from-flag".to_string()];
+
+        let (el, _) = settings.layer(&args, &env(&vars)).unwrap();
+        assert_eq!(el.chain, "hoodi");
+        assert_eq!(el.http_port, 3000);
+        assert_eq!(el.extra_args, ["--from-file", "--from-flag"]);
+    }
+
+    #[test]
+    fn env_picks_the_chain_table_from_the_file() {
+        let settings = Settings::from_toml(FILE).unwrap();
+        let (el, _) = settings
+            .layer(&NodeArgs::default(), &env(&[("ETHUP_CHAIN", "sepolia")]))
+            .unwrap();
+        assert_eq!(el.chain, "sepolia");
+        assert_eq!(el.http_port, 8545);
+    }
+
+    #[test]
+    fn invalid_env_values_name_the_variable() {
+        let Err(err) = Settings::default().layer(
+            &NodeArgs::default(),
+            &env(&[("ETHUP_EL_HTTP_PORT", "http")]),
+        ) else {
+            panic!("expected ETHUP_EL_HTTP_PORT=http to be rejected");
+        };
+        assert!(
+            err.to_string()
+                .starts_with("invalid value for ETHUP_EL_HTTP_PORT"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn unknown_chain_tables_are_named() {
+        assert_eq!(
+            error("[chains.hodi.el]\nhttp_port = 1"),
+            "unknown key `chains.hodi` (expected one of: mainnet, hoodi, sepolia, holesky)"
+        );
+    }
+
+    #[test]
+    fn unknown_clients_are_named() {
+        let expected = format!(
+            "unknown key `versions.rethh` (expected one of: {})",
+            client_names().join(", ")
+        );
+        assert_eq!(error("[versions]\nrethh = \"v1.3.12\""), expected);
+
+        let expected = format!(
+            "unknown key `verify.keys.tekku` (expected one of: {})",
+            client_names().join(", ")
+        );
+        assert_eq!(error("[verify.keys]\ntekku = \"ABCD\""), expected);
+    }
+
+    #[test]
+    fn unknown_fields_are_named() {
+        assert!(error("chian = \"hoodi\"").contains("unknown field `chian`"));
+        assert!(error("[logs]\nmax_size = 1").contains("unknown field `max_size`"));
+        assert!(error("[chains.hoodi.el]\nhttp = 1").contains("unknown field `http`"));
+    }
+}
@@ -1,71 +1,244 @@
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
+use std::time::Duration;
 
 use super::types::*;
+use crate::cli::StatusFormat;
 use crate::config::{ClConfig, ElConfig};
+use crate::daemon::State;
+
+pub async fn status(
+    el: &ElConfig,
+    cl: &ClConfig,
+    format: StatusFormat,
+    timeout: Duration,
+    daemon: Option<State>,
+) -> anyhow::Result<()> {
+    let client = Client::builder().timeout(timeout).build()?;
+    let report = collect(&client, el, cl, timeout, daemon).await;
+
+    match format {
+        StatusFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        StatusFormat::Yaml => print!("{}", serde_norway::to_string(&report)?),
+        StatusFormat::Table => print_table(&report, el.embedded_cl),
+    }
+
+    Ok(())
+}
+
+pub async fn collect(
+    client: &Client,
+    el: &ElConfig,
+    cl: &ClConfig,
+    timeout: Duration,
+    daemon: Option<State>,
+) -> StatusReport {
+    let cl_client = if el.embedded_cl {
+        el.client.to_string()
+    } else {
+        cl.client.to_string()
+    };
+
+    let (el_res, cl_res) = tokio::join!(el_status(client, el), cl_status(client, cl));
+
+    StatusReport {
+        schema_version: STATUS_SCHEMA_VERSION,
+        chain: el.chain.clone(),
+        expected_chain_id: el.chain_id,
+        daemon: daemon.as_ref().map(DaemonReport::new),
+        execution: ClientReport::new(
+            el.client.to_string(),
+            el.rpc_url(),
+            el_res.map_err(|e| describe_error(&e, timeout)),
+        ),
+        consensus: ClientReport::new(
+            cl_client,
+            cl.http_url(),
+            cl_res.map_err(|e| describe_error(&e, timeout)),
+        ),
+    }
+}
+
+// hands a fresh report to `on_report` every `interval` until it returns false
+pub fn spawn_poller<F>(
+    el: &ElConfig,
+    cl: &ClConfig,
+    interval: Duration,
+    timeout: Duration,
+    mut on_report: F,
+) where
+    F: FnMut(StatusReport) -> bool + Send + 'static,
+{
+    let (el, cl) = (el.clone(), cl.clone());
+
+    tokio::spawn(async move {
+        let Ok(client) = Client::builder().timeout(timeout).build() else {
+            return;
+        };
+
+        loop {
+            let report = collect(&client, &el, &cl, timeout, None).await;
+            if !on_report(report) {
+                return;
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
 
-pub async fn status(el: &ElConfig, cl: &ClConfig) -> anyhow::Result<()> {
-    let el_status = el_status(el).await?;
-    let cl_status = cl_status(cl).await?;
+fn print_processes(daemon: Option<&DaemonReport>) {
+    let Some(daemon) = daemon else {
+        println!("Managed processes: none (ethup is not running)");
+        println!();
+        return;
+    };
 
-    println!("Execution Client Running:");
-    println!("  Version: {}", el_status.version);
-    println!("  Chain ID: {}", el_status.chain_id);
-    println!("  Executed Blocks: {}", el_status.head_block);
-    println!("  Sync: {}", el_status.sync);
-    println!("  Peers: {}", el_status.peers);
+    let process = |p: &ProcessReport| match p.pid {
+        Some(pid) if p.alive => format!("pid {} (alive)", pid),
+        Some(pid) => format!("pid {} (dead)", pid),
+        None => "down, waiting to restart".to_string(),
+    };
 
+    println!("Managed processes ({}):", daemon.chain);
+    println!("  Supervisor: pid {} (alive)", daemon.supervisor_pid);
+    println!("  EL {}: {}", daemon.el.client, process(&daemon.el));
+    match daemon.cl {
+        Some(ref cl) => println!("  CL {}: {}", cl.client, process(cl)),
+        None => println!("  CL: embedded in {}", daemon.el.client),
+    }
     println!();
-    println!("Consensus Client Running:");
+}
+
+pub fn print_table(report: &StatusReport, embedded_cl: bool) {
+    print_processes(report.daemon.as_ref());
 
-    println!("  Version: {}", cl_status.version);
-    println!("  Head slot: {}", cl_status.head_slot);
+    let el = &report.execution;
+    match el.status {
+        Some(ref status) => {
+            println!("Execution Client Running:");
+            println!("  Version: {}", status.version);
+            if status.chain_id == report.expected_chain_id {
+                println!("  Chain ID: {}", status.chain_id);
+            } else {
+                println!(
+                    "  Chain ID: {} (expected {} for {})",
+                    status.chain_id, report.expected_chain_id, report.chain
+                );
+            }
+            println!("  Executed Blocks: {}", status.head_block);
+            println!("  Sync: {}", status.sync);
+            println!("  Peers: {}", status.peers);
+        }
+        None => print_unreachable("Execution", el),
+    }
+
+    println!();
+    let cl = &report.consensus;
+    let Some(ref status) = cl.status else {
+        print_unreachable("Consensus", cl);
+        return;
+    };
 
-    if let Some(finalized_epoch) = cl_status.finalized_epoch {
+    if embedded_cl {
+        println!("Consensus Client Running (embedded in {}):", cl.client);
+    } else {
+        println!("Consensus Client Running:");
+    }
+
+    println!("  Version: {}", status.version);
+    println!("  Head slot: {}", status.head_slot);
+
+    if let Some(finalized_epoch) = status.finalized_epoch {
         println!("  Finalized epoch: {}", finalized_epoch);
     }
 
     println!(
         "  Sync: {}",
-        if cl_status.is_syncing {
+        if status.is_syncing {
             "syncing"
         } else {
             "not syncing"
         }
     );
 
-    println!("  Health: {}", cl_status.health);
-    println!("  Peers: {}", cl_status.peers);
+    println!("  Health: {}", status.health);
+    println!("  Peers: {}", status.peers);
+}
 
-    Ok(())
+fn print_unreachable<T>(layer: &str, report: &ClientReport<T>) {
+    println!(
+        "{} Client ({}) at {}: {}",
+        layer,
+        report.client,
+        report.url,
+        report.error.as_deref().unwrap_or("unreachable")
+    );
 }
 
-pub async fn el_status(el: &ElConfig) -> anyhow::Result<ExecutionStatus> {
-    let version: String = el_rpc(el, "web3_clientVersion", json!([]))
+// turns transport errors into short phrases like "unreachable (connection
+// refused)" or "timed out after 5s"
+fn describe_error(e: &anyhow::Error, timeout: Duration) -> String {
+    let Some(err) = e.downcast_ref::<reqwest::Error>() else {
+        return format!("{:#}", e);
+    };
+
+    if err.is_timeout() {
+        return format!("timed out after {}", humantime::format_duration(timeout));
+    }
+    if err.is_connect() {
+        let mut source = std::error::Error::source(err);
+        while let Some(inner) = source {
+            if let Some(io) = inner.downcast_ref::<std::io::Error>() {
+                return format!("unreachable ({})", io_reason(io));
+            }
+            source = inner.source();
+        }
+        return "unreachable (connection failed)".to_string();
+    }
+
+    format!("{:#}", e)
+}
+
+fn io_reason(e: &std::io::Error) -> String {
+    use std::io::ErrorKind;
+
+    match e.kind() {
+        ErrorKind::ConnectionRefused => "connection refused".to_string(),
+        ErrorKind::ConnectionReset => "connection reset".to_string(),
+        ErrorKind::TimedOut => "connection timed out".to_string(),
+        ErrorKind::HostUnreachable | ErrorKind::NetworkUnreachable => {
+            "host unreachable".to_string()
+        }
+        _ => e.to_string(),
+    }
+}
+
+pub async fn el_status(client: &Client, el: &ElConfig) -> anyhow::Result<ExecutionStatus> {
+    let version: String = el_rpc(client, el, "web3_clientVersion", json!([]))
         .await?
         .as_str()
         .unwrap_or("?")
         .to_string();
 
-    let chain_id_hex: String = el_rpc(el, "eth_chainId", json!([]))
+    let chain_id_hex: String = el_rpc(client, el, "eth_chainId", json!([]))
         .await?
         .as_str()
         .unwrap_or("?")
         .to_string();
 
-    let head_hex: String = el_rpc(el, "eth_blockNumber", json!([]))
+    let head_hex: String = el_rpc(client, el, "eth_blockNumber", json!([]))
         .await?
         .as_str()
         .unwrap_or("?")
         .to_string();
 
     let syncing: ElSyncing = {
-        let raw = el_rpc(el, "eth_syncing", json!([])).await?;
+        let raw = el_rpc(client, el, "eth_syncing", json!([])).await?;
         serde_json::from_value(raw)?
     };
 
-    let peers_hex: String = el_rpc(el, "net_peerCount", json!([]))
+    let peers_hex: String = el_rpc(client, el, "net_peerCount", json!([]))
         .await?
         .as_str()
         .unwrap_or("?")
@@ -110,18 +283,22 @@ pub async fn el_status(el: &ElConfig) -> anyhow::Result<ExecutionStatus> {
     })
 }
 
-async fn cl_status(cl: &ClConfig) -> anyhow::Result<ConsensusStatus> {
-    let ver: ClApi<ClVersion> = cl_get(cl, "eth/v1/node/version").await?;
-    let sync: ClApi<ClSync> = cl_get(cl, "eth/v1/node/syncing").await?;
-    let health = cl_health(&cl.http_url()).await?;
+async fn cl_status(client: &Client, cl: &ClConfig) -> anyhow::Result<ConsensusStatus> {
+    let ver: ClApi<ClVersion> = cl_get(client, cl, "eth/v1/node/version").await?;
+    let sync: ClApi<ClSync> = cl_get(client, cl, "eth/v1/node/syncing").await?;
+    let health = cl_health(client, &cl.http_url()).await?;
 
     let head_slot = sync.data.head_slot.parse::<u64>()?;
     let finalized_epoch = match sync.data.finalized_epoch {
         Some(s) => Some(s.parse::<u64>()?),
         None => None,
     };
+    let sync_distance = match sync.data.sync_distance {
+        Some(s) => Some(s.parse::<u64>()?),
+        None => None,
+    };
 
-    let peers: Value = cl_get(cl, "eth/v1/node/peers").await?;
+    let peers: Value = cl_get(client, cl, "eth/v1/node/peers").await?;
     let peers = peers["data"].as_array().map(|a| a.len()).unwrap_or(0) as u64;
 
     Ok(ConsensusStatus {
@@ -129,14 +306,18 @@ async fn cl_status(cl: &ClConfig) -> anyhow::Result<ConsensusStatus> {
         head_slot,
         finalized_epoch,
         is_syncing: sync.data.is_syncing,
+        sync_distance,
         health,
         peers,
     })
 }
 
-async fn el_rpc(el: &ElConfig, method: &str, params: Value) -> anyhow::Result<Value> {
-    let client = Client::new();
-
+async fn el_rpc(
+    client: &Client,
+    el: &ElConfig,
+    method: &str,
+    params: Value,
+) -> anyhow::Result<Value> {
     let payload = json!({
         "jsonrpc": "2.0",
         "id": 1,
@@ -155,9 +336,13 @@ async fn el_rpc(el: &ElConfig, method: &str, params: Value) -> anyhow::Result<Va
     Ok(resp["result"].clone())
 }
 
-async fn cl_get<T: DeserializeOwned>(cl: &ClConfig, path: &str) -> anyhow::Result<T> {
+async fn cl_get<T: DeserializeOwned>(
+    client: &Client,
+    cl: &ClConfig,
+    path: &str,
+) -> anyhow::Result<T> {
     let url = format!("{}/{}", cl.http_url(), path);
-    let resp = reqwest::get(&url).await?;
+    let resp = client.get(&url).send().await?;
     let status = resp.status();
 
     if !status.is_success() {
@@ -168,9 +353,9 @@ async fn cl_get<T: DeserializeOwned>(cl: &ClConfig, path: &str) -> anyhow::Resul
     Ok(val)
 }
 
-async fn cl_health(cl_http_url: &str) -> anyhow::Result<ClHealth> {
+async fn cl_health(client: &Client, cl_http_url: &str) -> anyhow::Result<ClHealth> {
     let url = format!("{}/eth/v1/node/health", cl_http_url);
-    let resp = reqwest::get(url).await?;
+    let resp = client.get(url).send().await?;
     let code = resp.status().as_u16();
 
     let health = match code {
@@ -1,6 +1,94 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::daemon::{ProcessState, State, is_alive};
+
+// bump when a field is renamed or removed; new fields keep the version
+pub const STATUS_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub schema_version: u32,
+    pub chain: String,
+    pub expected_chain_id: u64,
+    pub daemon: Option<DaemonReport>,
+    pub execution: ClientReport<ExecutionStatus>,
+    pub consensus: ClientReport<ConsensusStatus>,
+}
+
+// what the report says about a running supervisor; kept apart from the
+// state file so that file can change without touching the schema
+#[derive(Serialize)]
+pub struct DaemonReport {
+    pub chain: String,
+    pub supervisor_pid: u32,
+    pub el: ProcessReport,
+    // None when the EL embeds its own consensus layer
+    pub cl: Option<ProcessReport>,
+}
+
+#[derive(Serialize)]
+pub struct ProcessReport {
+    pub client: String,
+    // None while the client is down, e.g. waiting to be restarted
+    pub pid: Option<u32>,
+    pub alive: bool,
+}
+
+impl DaemonReport {
+    pub fn new(state: &State) -> Self {
+        Self {
+            chain: state.chain.clone(),
+            supervisor_pid: state.supervisor_pid,
+            el: ProcessReport::new(&state.el),
+            cl: state.cl.as_ref().map(ProcessReport::new),
+        }
+    }
+}
+
+impl ProcessReport {
+    fn new(process: &ProcessState) -> Self {
+        Self {
+            client: process.client.clone(),
+            pid: process.pid,
+            alive: process.pid.is_some_and(is_alive),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ClientReport<T> {
+    pub client: String,
+    pub url: String,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub status: Option<T>,
+}
+
+impl<T> ClientReport<T> {
+    pub fn new(client: String, url: String, status: Result<T, String>) -> Self {
+        match status {
+            Ok(status) => Self {
+                client,
+                url,
+                reachable: true,
+                error: None,
+                status: Some(status),
+            },
+            Err(e) => Self {
+                client,
+                url,
+                reachable: false,
+                error: Some(e),
+                status: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
 pub struct ExecutionStatus {
     pub version: String,
     pub chain_id: u64,
@@ -9,6 +97,8 @@ pub struct ExecutionStatus {
     pub peers: u64,
 }
 
+#[derive(Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum ElSyncState {
     FullySynced,
     Syncing {
@@ -46,15 +136,19 @@ impl fmt::Display for ElSyncState {
     }
 }
 
+#[derive(Serialize)]
 pub struct ConsensusStatus {
     pub version: String,
     pub head_slot: u64,
     pub finalized_epoch: Option<u64>,
     pub is_syncing: bool,
+    pub sync_distance: Option<u64>,
     pub health: ClHealth,
     pub peers: u64,
 }
 
+#[derive(Serialize)]
+#[serde(tag = "status", content = "http_status", rename_all = "snake_case")]
 pub enum ClHealth {
     Healthy,
     Syncing,
@@ -76,7 +170,7 @@ impl fmt::Display for ClHealth {
 #[derive(Deserialize)]
 #[serde(untagged)]
 pub enum ElSyncing {
-    NotSyncing(bool),
+    NotSyncing(#[allow(dead_code)] bool),
     Syncing {
         #[serde(rename = "startingBlock")]
         starting_block: String,
@@ -102,4 +196,93 @@ pub struct ClSync {
     pub head_slot: String,
     pub is_syncing: bool,
     pub finalized_epoch: Option<String>,
+    pub sync_distance: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // any change to this output is a schema change, see STATUS_SCHEMA_VERSION
+    #[test]
+    fn json_schema_is_pinned() {
+        let report = StatusReport {
+            schema_version: STATUS_SCHEMA_VERSION,
+            chain: "hoodi".to_string(),
+            expected_chain_id: 560048,
+            daemon: Some(DaemonReport {
+                chain: "hoodi".to_string(),
+                supervisor_pid: 100,
+                el: ProcessReport {
+                    client: "reth".to_string(),
+                    pid: Some(101),
+                    alive: true,
+                },
+                cl: Some(ProcessReport {
+                    client: "lighthouse".to_string(),
+                    pid: None,
+                    alive: false,
+                }),
+            }),
+            execution: ClientReport::new(
+                "reth".to_string(),
+                "http://127.0.0.1:8545".to_string(),
+                Ok(ExecutionStatus {
+                    version: "reth/v1.3.12".to_string(),
+                    chain_id: 560048,
+                    head_block: 10,
+                    sync: ElSyncState::Syncing {
+                        starting_block: 0,
+                        current_block: 10,
+                        highest_block: 20,
+                        percent: 50.0,
+                    },
+                    peers: 3,
+                }),
+            ),
+            consensus: ClientReport::<ConsensusStatus>::new(
+                "lighthouse".to_string(),
+                "http://127.0.0.1:5052".to_string(),
+                Err("connection refused".to_string()),
+            ),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "schema_version": 2,
+                "chain": "hoodi",
+                "expected_chain_id": 560048,
+                "daemon": {
+                    "chain": "hoodi",
+                    "supervisor_pid": 100,
+                    "el": {"client": "reth", "pid": 101, "alive": true},
+                    "cl": {"client": "lighthouse", "pid": null, "alive": false},
+                },
+                "execution": {
+                    "client": "reth",
+                    "url": "http://127.0.0.1:8545",
+                    "reachable": true,
+                    "version": "reth/v1.3.12",
+                    "chain_id": 560048,
+                    "head_block": 10,
+                    "sync": {
+                        "state": "syncing",
+                        "starting_block": 0,
+                        "current_block": 10,
+                        "highest_block": 20,
+                        "percent": 50.0,
+                    },
+                    "peers": 3,
+                },
+                "consensus": {
+                    "client": "lighthouse",
+                    "url": "http://127.0.0.1:5052",
+                    "reachable": false,
+                    "error": "connection refused",
+                },
+            })
+        );
+    }
 }
@@ -0,0 +1,161 @@
+use reqwest::Client;
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use super::logic::{collect, print_table};
+use super::types::*;
+use crate::config::{ClConfig, ElConfig};
+use crate::daemon;
+
+// rates are averaged over this many refreshes to smooth out bursts
+const WINDOW: usize = 6;
+const SECONDS_PER_SLOT: f64 = 12.0;
+
+struct Sample {
+    at: Instant,
+    el_block: Option<u64>,
+    el_highest: Option<u64>,
+    el_peers: Option<u64>,
+    cl_slot: Option<u64>,
+    cl_distance: Option<u64>,
+    cl_peers: Option<u64>,
+}
+
+impl Sample {
+    fn new(report: &StatusReport) -> Self {
+        let el = report.execution.status.as_ref();
+        let cl = report.consensus.status.as_ref();
+
+        let (el_block, el_highest) = match el.map(|s| &s.sync) {
+            Some(ElSyncState::Syncing {
+                current_block,
+                highest_block,
+                ..
+            }) => (Some(*current_block), Some(*highest_block)),
+            Some(ElSyncState::FullySynced) => (el.map(|s| s.head_block), None),
+            None => (None, None),
+        };
+
+        Self {
+            at: Instant::now(),
+            el_block,
+            el_highest,
+            el_peers: el.map(|s| s.peers),
+            cl_slot: cl.map(|s| s.head_slot),
+            cl_distance: cl.filter(|s| s.is_syncing).and_then(|s| s.sync_distance),
+            cl_peers: cl.map(|s| s.peers),
+        }
+    }
+}
+
+pub async fn watch(
+    el: &ElConfig,
+    cl: &ClConfig,
+    timeout: Duration,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    let client = Client::builder().timeout(timeout).build()?;
+    let redraw = std::io::stdout().is_terminal();
+    let mut samples: VecDeque<Sample> = VecDeque::new();
+
+    loop {
+        let report = collect(&client, el, cl, timeout, daemon::running(&el.chain)?).await;
+        let sample = Sample::new(&report);
+
+        if redraw {
+            // clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        print_table(&report, el.embedded_cl);
+        println!();
+        print_progress(&sample, &samples);
+        println!();
+        println!(
+            "Refreshing every {}, Ctrl+C to exit",
+            humantime::format_duration(interval)
+        );
+
+        if samples.len() == WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+fn print_progress(now: &Sample, history: &VecDeque<Sample>) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else {
+        println!("Progress: measuring...");
+        return;
+    };
+    let elapsed = now.at.duration_since(first.at).as_secs_f64();
+
+    println!("Progress:");
+
+    if let Some(rate) = rate(first.el_block, now.el_block, elapsed) {
+        let eta = match (now.el_block, now.el_highest) {
+            (Some(current), Some(highest)) => eta(highest.saturating_sub(current), rate),
+            _ => "synced".to_string(),
+        };
+        println!("  EL: {:.1} blocks/s, {}", rate, eta);
+    }
+
+    if let Some(rate) = rate(first.cl_slot, now.cl_slot, elapsed) {
+        // the head keeps moving one slot every 12s while we catch up
+        let eta = match now.cl_distance {
+            Some(distance) => eta(distance, rate - 1.0 / SECONDS_PER_SLOT),
+            None => "synced".to_string(),
+        };
+        println!("  CL: {:.2} slots/s, {}", rate, eta);
+    }
+
+    println!(
+        "  Peers: EL {} {}, CL {} {}",
+        display(now.el_peers),
+        trend(last.el_peers, now.el_peers),
+        display(now.cl_peers),
+        trend(last.cl_peers, now.cl_peers)
+    );
+}
+
+fn rate(from: Option<u64>, to: Option<u64>, elapsed: f64) -> Option<f64> {
+    let (from, to) = (from?, to?);
+    if elapsed <= 0.0 {
+        return None;
+    }
+    Some(to.saturating_sub(from) as f64 / elapsed)
+}
+
+fn eta(remaining: u64, rate: f64) -> String {
+    if remaining == 0 {
+        return "synced".to_string();
+    }
+    if rate <= 0.0 {
+        return format!("{} behind, not catching up", remaining);
+    }
+
+    let secs = Duration::from_secs((remaining as f64 / rate) as u64);
+    format!(
+        "{} behind, ~{} to sync",
+        remaining,
+        humantime::format_duration(secs)
+    )
+}
+
+fn trend(before: Option<u64>, now: Option<u64>) -> &'static str {
+    match (before, now) {
+        (Some(b), Some(n)) if n > b => "↑",
+        (Some(b), Some(n)) if n < b => "↓",
+        (Some(_), Some(_)) => "→",
+        _ => "",
+    }
+}
+
+fn display(value: Option<u64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
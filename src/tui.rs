@@ -0,0 +1,449 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use regex::Regex;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::task::JoinHandle;
+
+use crate::config::{ClConfig, ElConfig};
+use crate::runner::Role;
+use crate::status::logic::spawn_poller;
+use crate::status::types::{ClientReport, ConsensusStatus, ExecutionStatus, StatusReport};
+
+const SCROLLBACK: usize = 10_000;
+const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub enum Event {
+    Line {
+        role: Role,
+        stderr: bool,
+        text: String,
+    },
+    Notice(String),
+    Status(Box<StatusReport>),
+    Key(KeyEvent),
+    Exit,
+}
+
+// requests from the TUI to the supervisor loop in runner.rs
+pub enum Control {
+    Restart(Role),
+    // restarts the client with debug logging turned on or back off
+    ToggleDebug(Role),
+    Quit,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    // picks the level out of the first few words of a log line; clients
+    // spell it INFO, INF, info or level=info
+    fn detect(line: &str) -> Option<Self> {
+        let head: String = line.chars().take(80).collect();
+        head.split(|c: char| !c.is_ascii_alphabetic())
+            .find_map(|word| match word.to_ascii_uppercase().as_str() {
+                "TRACE" | "TRCE" | "TRC" => Some(Level::Trace),
+                "DEBUG" | "DBUG" | "DBG" => Some(Level::Debug),
+                "INFO" | "INF" => Some(Level::Info),
+                "WARN" | "WARNING" | "WRN" => Some(Level::Warn),
+                "ERROR" | "ERRO" | "ERR" | "CRIT" | "FATAL" => Some(Level::Error),
+                _ => None,
+            })
+    }
+}
+
+struct LogLine {
+    level: Option<Level>,
+    stderr: bool,
+    text: String,
+}
+
+struct Pane {
+    role: Role,
+    client: String,
+    lines: VecDeque<LogLine>,
+    // lines scrolled up from the bottom; 0 follows new output
+    scroll: usize,
+    filter: String,
+    // debug logging requested with `l`
+    debug: bool,
+}
+
+impl Pane {
+    fn new(role: Role, client: String) -> Self {
+        Self {
+            role,
+            client,
+            lines: VecDeque::new(),
+            scroll: 0,
+            filter: String::new(),
+            debug: false,
+        }
+    }
+
+    fn push(&mut self, line: LogLine) {
+        if self.lines.len() == SCROLLBACK {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+    }
+
+    fn visible(&self) -> Vec<&LogLine> {
+        self.lines
+            .iter()
+            .filter(|l| self.filter.is_empty() || l.text.contains(&self.filter))
+            .collect()
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max = self.visible().len();
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+}
+
+struct App {
+    el: Pane,
+    cl: Pane,
+    focus: Role,
+    status: Option<Box<StatusReport>>,
+    notice: String,
+    embedded_cl: bool,
+    // text typed after `/` until Enter or Esc
+    editing: Option<String>,
+}
+
+impl App {
+    fn pane(&mut self, role: Role) -> &mut Pane {
+        match role {
+            Role::El => &mut self.el,
+            Role::Cl => &mut self.cl,
+        }
+    }
+
+    fn handle(&mut self, event: Event, control: &UnboundedSender<Control>, ansi: &Regex) {
+        match event {
+            Event::Line { role, stderr, text } => {
+                let text = ansi.replace_all(&text, "").into_owned();
+                self.pane(role).push(LogLine {
+                    level: Level::detect(&text),
+                    stderr,
+                    text,
+                });
+            }
+            Event::Notice(msg) => self.notice = msg,
+            Event::Status(report) => self.status = Some(report),
+            Event::Key(key) => self.key(key, control),
+            Event::Exit => {}
+        }
+    }
+
+    fn key(&mut self, key: KeyEvent, control: &UnboundedSender<Control>) {
+        if let Some(ref mut input) = self.editing {
+            match key.code {
+                KeyCode::Enter => {
+                    let filter = std::mem::take(input);
+                    self.editing = None;
+                    let pane = self.pane(self.focus);
+                    pane.filter = filter;
+                    pane.scroll = 0;
+                }
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        let ctrl_c =
+            key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+        let page = 10;
+        match key.code {
+            KeyCode::Char('q') => {
+                let _ = control.send(Control::Quit);
+            }
+            _ if ctrl_c => {
+                let _ = control.send(Control::Quit);
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Role::El => Role::Cl,
+                    Role::Cl => Role::El,
+                }
+            }
+            KeyCode::Char('r') => {
+                let _ = control.send(Control::Restart(self.focus));
+            }
+            KeyCode::Char('l') if self.focus == Role::Cl && self.embedded_cl => {
+                self.notice =
+                    "The CL is embedded in the EL, change the EL's log level instead".to_string();
+            }
+            KeyCode::Char('l') => {
+                let pane = self.pane(self.focus);
+                pane.debug = !pane.debug;
+                let _ = control.send(Control::ToggleDebug(self.focus));
+            }
+            KeyCode::Char('/') => self.editing = Some(String::new()),
+            KeyCode::Esc => self.pane(self.focus).filter.clear(),
+            KeyCode::Up | KeyCode::Char('k') => self.pane(self.focus).scroll_by(1),
+            KeyCode::Down | KeyCode::Char('j') => self.pane(self.focus).scroll_by(-1),
+            KeyCode::PageUp => self.pane(self.focus).scroll_by(page),
+            KeyCode::PageDown => self.pane(self.focus).scroll_by(-page),
+            KeyCode::End | KeyCode::Char('G') => self.pane(self.focus).scroll = 0,
+            _ => {}
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, el, cl, footer] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_header(frame, header);
+        self.draw_pane(frame, el, &self.el, Color::Green);
+        self.draw_pane(frame, cl, &self.cl, Color::Blue);
+
+        let footer_text = match self.editing {
+            Some(ref input) => format!("filter {}: {}_", self.focus.name(), input),
+            None if !self.notice.is_empty() => self.notice.clone(),
+            None => "q quit  tab switch pane  r restart  l debug logs  / filter  esc clear  ↑↓ pgup pgdn scroll  end follow".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(footer_text).style(Style::default().add_modifier(Modifier::DIM)),
+            footer,
+        );
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let lines = match self.status {
+            Some(ref report) => vec![
+                el_summary(&report.execution, report.expected_chain_id),
+                cl_summary(&report.consensus),
+            ],
+            None => vec![Line::from("waiting for status...")],
+        };
+
+        let title = match self.status {
+            Some(ref report) => format!(" ethup · {} ", report.chain),
+            None => " ethup ".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+
+    fn draw_pane(&self, frame: &mut Frame, area: Rect, pane: &Pane, color: Color) {
+        let visible = pane.visible();
+        let height = area.height.saturating_sub(2) as usize;
+        let end = visible.len().saturating_sub(pane.scroll);
+        let start = end.saturating_sub(height);
+
+        let lines: Vec<Line> = visible[start..end]
+            .iter()
+            .map(|l| {
+                let style = match l.level {
+                    Some(Level::Error) => Style::default().fg(Color::Red),
+                    Some(Level::Warn) => Style::default().fg(Color::Yellow),
+                    _ if l.stderr => Style::default().fg(Color::Red),
+                    _ => Style::default(),
+                };
+                Line::styled(l.text.as_str(), style)
+            })
+            .collect();
+
+        let mut title = format!(" {} {} ", pane.role.name(), pane.client);
+        if pane.debug {
+            title.push_str("· debug logs ");
+        }
+        if !pane.filter.is_empty() {
+            title.push_str(&format!("· filter \"{}\" ", pane.filter));
+        }
+        if pane.scroll > 0 {
+            title.push_str(&format!("· scrolled {} ", pane.scroll));
+        }
+
+        let border = if self.focus == pane.role {
+            Style::default().fg(color).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(color)
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(title),
+            ),
+            area,
+        );
+    }
+}
+
+fn el_summary(report: &ClientReport<ExecutionStatus>, expected_chain_id: u64) -> Line<'static> {
+    let label = Span::styled(
+        format!("EL {:<10} ", report.client),
+        Style::default().fg(Color::Green),
+    );
+    let Some(ref s) = report.status else {
+        return Line::from(vec![label, Span::raw(unreachable(report))]);
+    };
+
+    let mut text = format!(
+        "{} · block {} · {} · {} peers",
+        s.version, s.head_block, s.sync, s.peers
+    );
+    if s.chain_id != expected_chain_id {
+        text.push_str(&format!(
+            " · chain id {} (expected {})",
+            s.chain_id, expected_chain_id
+        ));
+    }
+    Line::from(vec![label, Span::raw(text)])
+}
+
+fn cl_summary(report: &ClientReport<ConsensusStatus>) -> Line<'static> {
+    let label = Span::styled(
+        format!("CL {:<10} ", report.client),
+        Style::default().fg(Color::Blue),
+    );
+    let Some(ref s) = report.status else {
+        return Line::from(vec![label, Span::raw(unreachable(report))]);
+    };
+
+    let sync = if s.is_syncing { "syncing" } else { "synced" };
+    let mut text = format!(
+        "{} · slot {} · {} · health {} · {} peers",
+        s.version, s.head_slot, sync, s.health, s.peers
+    );
+    if let Some(epoch) = s.finalized_epoch {
+        text.push_str(&format!(" · finalized epoch {}", epoch));
+    }
+    Line::from(vec![label, Span::raw(text)])
+}
+
+fn unreachable<T>(report: &ClientReport<T>) -> String {
+    report
+        .error
+        .clone()
+        .unwrap_or_else(|| "unreachable".to_string())
+}
+
+pub struct Tui {
+    pub events: UnboundedSender<Event>,
+    task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl Tui {
+    // takes over the terminal until close(); keys that need the supervisor
+    // come back on the returned receiver
+    pub fn start(
+        el: &ElConfig,
+        cl: &ClConfig,
+    ) -> anyhow::Result<(Self, UnboundedReceiver<Control>)> {
+        let (events, rx) = unbounded_channel();
+        let (control_tx, control) = unbounded_channel();
+
+        let cl_client = if el.embedded_cl {
+            el.client.to_string()
+        } else {
+            cl.client.to_string()
+        };
+        let app = App {
+            el: Pane::new(Role::El, el.client.to_string()),
+            cl: Pane::new(Role::Cl, cl_client),
+            focus: Role::El,
+            status: None,
+            notice: String::new(),
+            embedded_cl: el.embedded_cl,
+            editing: None,
+        };
+
+        let status_tx = events.clone();
+        spawn_poller(el, cl, STATUS_INTERVAL, STATUS_TIMEOUT, move |report| {
+            status_tx.send(Event::Status(Box::new(report))).is_ok()
+        });
+        spawn_key_reader(events.clone());
+
+        let terminal = ratatui::try_init()?;
+        let task = tokio::spawn(ui_loop(terminal, app, rx, control_tx));
+
+        Ok((Self { events, task }, control))
+    }
+
+    pub async fn close(self) -> anyhow::Result<()> {
+        let _ = self.events.send(Event::Exit);
+        let res = self.task.await;
+        ratatui::restore();
+        res?
+    }
+}
+
+async fn ui_loop(
+    mut terminal: DefaultTerminal,
+    mut app: App,
+    mut rx: UnboundedReceiver<Event>,
+    control: UnboundedSender<Control>,
+) -> anyhow::Result<()> {
+    let ansi = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]")?;
+
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let Some(event) = rx.recv().await else {
+            return Ok(());
+        };
+        if let Event::Exit = event {
+            return Ok(());
+        }
+        app.handle(event, &control, &ansi);
+
+        // log lines come in bursts, so redraw once per batch
+        while let Ok(event) = rx.try_recv() {
+            if let Event::Exit = event {
+                return Ok(());
+            }
+            app.handle(event, &control, &ansi);
+        }
+    }
+}
+
+fn spawn_key_reader(events: UnboundedSender<Event>) {
+    std::thread::spawn(move || {
+        while !events.is_closed() {
+            match event::poll(Duration::from_millis(200)) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(_) => return,
+            }
+            if let Ok(TermEvent::Key(key)) = event::read()
+                && events.send(Event::Key(key)).is_err()
+            {
+                return;
+            }
+        }
+    });
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::daemon;
+use crate::install::{ClientInstaller, Staged, installer, migrate_legacy_installs};
+use crate::layout::bin_dir;
+use crate::manifest::Manifest;
+use crate::settings::Settings;
+
+pub async fn upgrade(
+    target: &str,
+    settings: &Settings,
+    stop_timeout: Duration,
+) -> anyhow::Result<()> {
+    migrate_legacy_installs().await?;
+    let manifest = Manifest::load()?;
+
+    let clients: Vec<&str> = if target == "all" {
+        manifest.clients.keys().map(String::as_str).collect()
+    } else {
+        installer(target)?;
+        if manifest.version(target).is_none() {
+            anyhow::bail!("{} is not installed; use `eth install {}`", target, target);
+        }
+        vec![target]
+    };
+
+    let mirror = settings.mirror()?;
+    let mut staged: Vec<(&ClientInstaller, Staged)> = Vec::new();
+    for client in clients {
+        let installer = installer(client)?;
+        let current = manifest.version(client).unwrap_or_default();
+
+        if !bin_dir().join(installer.binary()).exists() {
+            println!("{} is not installed, skipping", client);
+            continue;
+        }
+        if let Some(pin) = settings.versions.get(client) {
+            println!(
+                "{} is pinned to {} in the config file, skipping",
+                client, pin
+            );
+            continue;
+        }
+
+        let release = installer.release(None, mirror.as_ref()).await?;
+        if release.tag_name == current {
+            println!("{} {} is up to date", client, current);
+            continue;
+        }
+
+        println!("Upgrading {} {} -> {}", client, current, release.tag_name);
+        println!(
+            "  changelog: {}",
+            installer.changelog_url(&release.tag_name)
+        );
+        staged.push((installer, installer.stage(release, &settings.verify).await?));
+    }
+
+    if staged.is_empty() {
+        return Ok(());
+    }
+
+    // a running node only has to go down if it uses one of the new binaries;
+    // background nodes are stopped for the swap, foreground ones restart
+    // their clients in place afterwards so their terminal stays attached
+    let mut stopped = Vec::new();
+    let mut foreground = Vec::new();
+    for state in daemon::running_all()? {
+        if !staged
+            .iter()
+            .any(|(installer, _)| state.uses(installer.name))
+        {
+            continue;
+        }
+        if state.detached {
+            stopped.extend(daemon::stop(&state.chain, stop_timeout).await?);
+        } else {
+            foreground.push(state);
+        }
+    }
+
+    // failures are collected rather than returned so a stopped node always
+    // gets started again
+    let mut failed = Vec::new();
+    for (installer, release) in staged {
+        let previous = installer.current_version();
+        let checked = match installer.activate(&release) {
+            Ok(()) => installer.smoke_check().await,
+            Err(e) => Err(e),
+        };
+
+        match checked {
+            Ok(version) => {
+                if let Err(e) = Manifest::record(installer.name, &release.tag) {
+                    eprintln!(
+                        "{} {} is installed but could not be recorded: {}",
+                        installer.name, release.tag, e
+                    );
+                    failed.push(installer.name);
+                    continue;
+                }
+                println!(
+                    "Upgraded {} to {} ({})",
+                    installer.name, release.tag, version
+                );
+                if let Some(ref previous) = previous {
+                    println!("  roll back with `eth use {} {}`", installer.name, previous);
+                }
+            }
+            Err(e) => {
+                if let Err(rollback) = installer.rollback(&release.tag, previous.as_deref()) {
+                    eprintln!(
+                        "{} {} could not be installed ({}) nor rolled back: {}",
+                        installer.name, release.tag, e, rollback
+                    );
+                    failed.push(installer.name);
+                    continue;
+                }
+                eprintln!(
+                    "{} {} could not be installed, rolled back: {}",
+                    installer.name, release.tag, e
+                );
+                failed.push(installer.name);
+            }
+        }
+    }
+
+    // every node is brought back even if another one fails to
+    let mut not_restarted = Vec::new();
+    for state in &stopped {
+        if let Err(e) = daemon::start(&state.chain, &state.args).await {
+            eprintln!("the node on {} could not be started: {}", state.chain, e);
+            not_restarted.push(state.chain.as_str());
+        }
+    }
+    for state in &foreground {
+        if let Err(e) = daemon::restart_in_place(state, stop_timeout).await {
+            eprintln!("the node on {} could not be restarted: {}", state.chain, e);
+            not_restarted.push(state.chain.as_str());
+        }
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!("upgrade failed for {}", failed.join(", "));
+    }
+    if !not_restarted.is_empty() {
+        anyhow::bail!("could not restart the node on {}", not_restarted.join(", "));
+    }
+
+    Ok(())
+}
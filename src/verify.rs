@@ -0,0 +1,220 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::install::{Asset, Release, fetch_text};
+use crate::layout::{gnupg_dir, keys_dir};
+use crate::settings::VerifySettings;
+
+const KEYSERVER: &str = "hkps://keys.openpgp.org";
+
+// release signing keys published by the client teams; [verify.keys] in the
+// config file overrides these or adds keys for other clients
+const RELEASE_KEYS: &[(&str, &str)] = &[
+    ("reth", "50FB7CC55B2E8AFA59FE03B7AA5ED56A7FBF253E"),
+    ("lighthouse", "15E66D941F697E28F49381F426416DC3F30674B0"),
+];
+
+pub async fn verify_download(
+    client: &str,
+    release: &Release,
+    asset: &Asset,
+    path: &Path,
+    settings: &VerifySettings,
+) -> anyhow::Result<()> {
+    let res = verify(client, release, asset, path, settings).await;
+    if res.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    res
+}
+
+async fn verify(
+    client: &str,
+    release: &Release,
+    asset: &Asset,
+    path: &Path,
+    settings: &VerifySettings,
+) -> anyhow::Result<()> {
+    if settings.checksum {
+        verify_checksum(release, asset, path).await?;
+    }
+
+    if settings.signature {
+        verify_signature(client, release, asset, path, settings).await?;
+    }
+
+    Ok(())
+}
+
+async fn verify_checksum(release: &Release, asset: &Asset, path: &Path) -> anyhow::Result<()> {
+    let Some((algo, expected)) = expected_digest(release, asset).await? else {
+        anyhow::bail!(
+            "no checksum published for {}; set `verify.checksum = false` in the config file to install it anyway",
+            asset.name
+        );
+    };
+
+    let actual = match algo.as_str() {
+        "sha256" => hash_file::<Sha256>(path)?,
+        "md5" => hash_file::<Md5>(path)?,
+        other => anyhow::bail!("unsupported digest {} for {}", other, asset.name),
+    };
+    if !actual.eq_ignore_ascii_case(&expected) {
+        anyhow::bail!(
+            "checksum mismatch for {}: expected {} {}, got {}; the download was rejected",
+            asset.name,
+            algo,
+            expected,
+            actual
+        );
+    }
+
+    println!("Verified {} of {}", algo, asset.name);
+    Ok(())
+}
+
+async fn expected_digest(
+    release: &Release,
+    asset: &Asset,
+) -> anyhow::Result<Option<(String, String)>> {
+    for suffix in [".sha256", ".sha256sum"] {
+        let name = format!("{}{}", asset.name, suffix);
+        if let Some(sums) = release.assets.iter().find(|a| a.name == name) {
+            let body = fetch_text(&sums.browser_download_url).await?;
+            return Ok(body
+                .split_whitespace()
+                .next()
+                .map(|h| ("sha256".to_string(), h.to_string())));
+        }
+    }
+
+    Ok(asset
+        .digest
+        .as_deref()
+        .and_then(|d| d.split_once(':'))
+        .map(|(algo, hash)| (algo.to_string(), hash.to_string())))
+}
+
+pub fn hash_file<D: Digest + std::io::Write>(path: &Path) -> anyhow::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = D::new();
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+async fn verify_signature(
+    client: &str,
+    release: &Release,
+    asset: &Asset,
+    path: &Path,
+    settings: &VerifySettings,
+) -> anyhow::Result<()> {
+    let pinned = settings.keys.get(client).map(String::as_str).or_else(|| {
+        RELEASE_KEYS
+            .iter()
+            .find(|(name, _)| *name == client)
+            .map(|(_, key)| *key)
+    });
+    let Some(fingerprint) = pinned else {
+        anyhow::bail!(
+            "signature verification is enabled but no key is pinned for {}; add it under [verify.keys]",
+            client
+        );
+    };
+    let fingerprint = fingerprint.replace(' ', "").to_uppercase();
+
+    let sig_name = format!("{}.asc", asset.name);
+    let Some(sig_asset) = release.assets.iter().find(|a| a.name == sig_name) else {
+        anyhow::bail!("no signature {} published for {}", sig_name, asset.name);
+    };
+
+    let sig_path = path.with_extension("asc");
+    tokio::fs::write(
+        &sig_path,
+        fetch_text(&sig_asset.browser_download_url).await?,
+    )
+    .await?;
+
+    import_key(client, &fingerprint).await?;
+
+    let output = gpg()
+        .arg("--status-fd")
+        .arg("1")
+        .arg("--verify")
+        .arg(&sig_path)
+        .arg(path)
+        .output()
+        .await?;
+    let _ = std::fs::remove_file(&sig_path);
+
+    // VALIDSIG <signing key fpr> ... <primary key fpr>
+    let status = String::from_utf8_lossy(&output.stdout);
+    let valid = status
+        .lines()
+        .filter_map(|l| l.strip_prefix("[GNUPG:] VALIDSIG "))
+        .any(|l| {
+            let fields: Vec<&str> = l.split_whitespace().collect();
+            fields.first() == Some(&fingerprint.as_str())
+                || fields.last() == Some(&fingerprint.as_str())
+        });
+
+    if !valid {
+        anyhow::bail!(
+            "signature check failed for {}: not signed by pinned key {}; the download was rejected",
+            asset.name,
+            fingerprint
+        );
+    }
+
+    println!("Verified signature of {} by {}", asset.name, fingerprint);
+    Ok(())
+}
+
+async fn import_key(client: &str, fingerprint: &str) -> anyhow::Result<()> {
+    std::fs::create_dir_all(gnupg_dir())?;
+    std::fs::set_permissions(gnupg_dir(), std::fs::Permissions::from_mode(0o700))?;
+
+    let local = keys_dir().join(format!("{}.asc", client));
+    let mut cmd = gpg();
+    if local.exists() {
+        cmd.arg("--import").arg(local);
+    } else {
+        cmd.arg("--keyserver")
+            .arg(KEYSERVER)
+            .arg("--recv-keys")
+            .arg(fingerprint);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("signature verification needs gpg installed: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "failed to import key {} for {}: {}",
+            fingerprint,
+            client,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+fn gpg() -> Command {
+    let mut cmd = Command::new("gpg");
+    cmd.env("GNUPGHOME", gnupg_dir())
+        .arg("--batch")
+        .arg("--no-tty")
+        .stdin(Stdio::null());
+    cmd
+}
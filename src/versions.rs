@@ -0,0 +1,117 @@
+use crate::daemon;
+use crate::install::{INSTALLERS, installer};
+use crate::manifest::Manifest;
+use crate::settings::Settings;
+
+pub fn list() -> anyhow::Result<()> {
+    let mut any = false;
+
+    for installer in INSTALLERS {
+        let versions = installer.installed_versions()?;
+        if versions.is_empty() {
+            continue;
+        }
+        any = true;
+
+        let current = installer.current_version();
+        println!("{}", installer.name);
+        for version in versions {
+            let marker = if current.as_deref() == Some(version.as_str()) {
+                "*"
+            } else {
+                " "
+            };
+            println!("  {} {}", marker, version);
+        }
+    }
+
+    if !any {
+        println!("no clients installed");
+    }
+
+    Ok(())
+}
+
+pub fn use_version(client: &str, version: &str, settings: &Settings) -> anyhow::Result<()> {
+    let installer = installer(client)?;
+    let tag = installer.normalize_version(version);
+
+    if !installer.version_dir(&tag).is_dir() {
+        anyhow::bail!(
+            "{} {} is not installed; use `eth install {}@{}`",
+            client,
+            tag,
+            client,
+            tag
+        );
+    }
+
+    installer.use_version(&tag)?;
+    Manifest::record(client, &tag)?;
+    println!("Using {} {}", client, tag);
+
+    if let Some(pin) = settings.versions.get(client)
+        && installer.normalize_version(pin) != tag
+    {
+        println!(
+            "note: {} is pinned to {} in the config file, `eth run` will switch back",
+            client, pin
+        );
+    }
+    for state in daemon::running_all()? {
+        if state.uses(client) {
+            println!(
+                "Run `eth restart --chain {}` to pick up the new version",
+                state.chain
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn uninstall(spec: &str) -> anyhow::Result<()> {
+    let (client, version) = match spec.split_once('@') {
+        Some((client, version)) => (client, Some(version)),
+        None => (spec, None),
+    };
+    let installer = installer(client)?;
+
+    let versions = match version {
+        Some(v) => vec![installer.normalize_version(v)],
+        None => installer.installed_versions()?,
+    };
+    if versions.is_empty() || !versions.iter().all(|v| installer.version_dir(v).is_dir()) {
+        anyhow::bail!("{} is not installed", spec);
+    }
+
+    let current = installer.current_version();
+    if let Some(ref current) = current
+        && versions.contains(current)
+    {
+        if let Some(state) = daemon::running_all()?
+            .into_iter()
+            .find(|state| state.uses(client))
+        {
+            anyhow::bail!(
+                "{} {} is in use by the node on {}; stop it first with `eth stop --chain {}`",
+                client,
+                current,
+                state.chain,
+                state.chain
+            );
+        }
+
+        installer.unlink()?;
+        let mut manifest = Manifest::load()?;
+        manifest.clients.remove(client);
+        manifest.save()?;
+    }
+
+    for version in versions {
+        installer.remove_version(&version)?;
+        println!("Removed {} {}", client, version);
+    }
+
+    Ok(())
+}